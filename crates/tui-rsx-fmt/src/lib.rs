@@ -0,0 +1,229 @@
+//! Formats the RSX inside `view!`, `mount!` and `prop!` invocations, which
+//! rustfmt leaves alone. Rust expressions in braces are formatted with
+//! prettyplease, and other attribute values are kept as they're written.
+//! Comments aren't part of the tokens being formatted, so a block containing
+//! one is kept as it's written, and so is a macro with one anywhere else.
+
+use proc_macro2::{Delimiter, LineColumn, Span, TokenStream, TokenTree};
+use std::{fmt, str::FromStr};
+use syn::{spanned::Spanned, visit::Visit, Macro, MacroDelimiter};
+
+mod rsx;
+
+/// The macros whose bodies are RSX.
+const RSX_MACROS: &[&str] = &["view", "mount", "prop"];
+
+/// When the attributes of an element are put on separate lines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AttributeLayout {
+    /// On the same line as the tag unless they don't fit, or there are more
+    /// than [`Config::max_attributes`].
+    #[default]
+    Auto,
+    /// One per line whenever there's more than one.
+    OnePerLine,
+    /// Always on the same line as the tag.
+    SameLine,
+}
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub max_width: usize,
+    pub tab_spaces: usize,
+    pub attributes: AttributeLayout,
+    pub max_attributes: Option<usize>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_width: 100,
+            tab_spaces: 4,
+            attributes: AttributeLayout::Auto,
+            max_attributes: None,
+        }
+    }
+}
+
+/// A file that couldn't be parsed, at a 1-based line and column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Error {
+    fn new(span: Span, message: impl fmt::Display) -> Self {
+        let start = span.start();
+        Self {
+            message: message.to_string(),
+            line: start.line,
+            column: start.column + 1,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Formats every RSX macro in a Rust source file. The rest of the file is
+/// returned unchanged.
+pub fn format_source(source: &str, config: &Config) -> Result<String, Error> {
+    let file = syn::parse_file(source).map_err(|e| Error::new(e.span(), &e))?;
+    let mut finder = MacroFinder::default();
+    finder.visit_file(&file);
+
+    let source = Source::new(source);
+    let mut edits = vec![];
+    for mac in finder.macros {
+        let (start, end) = source.delimiters(&mac);
+        let indent = source.line_indent(mac.path.span().start());
+        let column = mac.delimiter_span().start().column;
+        let body = rsx::format_macro_body(&source, config, &mac, indent, column)?;
+        edits.push((start, end, body));
+    }
+
+    let mut out = source.text.to_string();
+    for (start, end, body) in edits.into_iter().rev() {
+        out.replace_range(start..end, &body);
+    }
+    Ok(out)
+}
+
+/// Whether `tokens` contain an invocation of an RSX macro.
+fn contains_rsx_macro(tokens: TokenStream) -> bool {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) if RSX_MACROS.iter().any(|name| ident == name) => {
+                if matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '!')
+                {
+                    return true;
+                }
+            }
+            TokenTree::Group(group) if contains_rsx_macro(group.stream()) => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Collects the spans that `tokens` cover. Braced groups are covered as a
+/// whole unless `in_braces` is set.
+fn token_spans(tokens: TokenStream, in_braces: bool, spans: &mut Vec<Span>) {
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) if in_braces || group.delimiter() != Delimiter::Brace => {
+                spans.push(group.span_open());
+                token_spans(group.stream(), in_braces, spans);
+                spans.push(group.span_close());
+            }
+            token => spans.push(token.span()),
+        }
+    }
+}
+
+fn is_rsx_macro(mac: &Macro) -> bool {
+    RSX_MACROS.iter().any(|name| mac.path.is_ident(name))
+}
+
+/// Collects the outermost RSX macros. Macros nested inside them are formatted
+/// along with their parent.
+#[derive(Default)]
+struct MacroFinder {
+    macros: Vec<Macro>,
+}
+
+impl<'ast> Visit<'ast> for MacroFinder {
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        if is_rsx_macro(mac) {
+            self.macros.push(mac.clone());
+        }
+    }
+}
+
+trait DelimiterSpan {
+    fn delimiter_span(&self) -> Span;
+}
+
+impl DelimiterSpan for Macro {
+    fn delimiter_span(&self) -> Span {
+        match &self.delimiter {
+            MacroDelimiter::Paren(paren) => paren.span.join(),
+            MacroDelimiter::Brace(brace) => brace.span.join(),
+            MacroDelimiter::Bracket(bracket) => bracket.span.join(),
+        }
+    }
+}
+
+/// The text of the file being formatted, for mapping spans to offsets.
+pub(crate) struct Source<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> Source<'a> {
+    fn new(text: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { text, line_starts }
+    }
+
+    /// The byte offset of a line and column. Columns count characters.
+    fn offset(&self, position: LineColumn) -> usize {
+        let start = self.line_starts[position.line - 1];
+        self.text[start..]
+            .char_indices()
+            .nth(position.column)
+            .map_or(self.text.len(), |(i, _)| start + i)
+    }
+
+    /// The text covered by `span`.
+    pub(crate) fn slice(&self, span: Span) -> &'a str {
+        &self.text[self.offset(span.start())..self.offset(span.end())]
+    }
+
+    /// The indentation of the line `position` is on.
+    pub(crate) fn line_indent(&self, position: LineColumn) -> usize {
+        let start = self.line_starts[position.line - 1];
+        self.text[start..].chars().take_while(|c| *c == ' ').count()
+    }
+
+    /// Whether `span`, which starts and ends with a delimiter, contains a
+    /// comment. Comments are the only text between tokens other than
+    /// whitespace. Those in nested braces are only found if `in_braces` is set.
+    pub(crate) fn has_comments(&self, span: Span, in_braces: bool) -> bool {
+        let text = Source::new(self.slice(span));
+        let Ok(tokens) = TokenStream::from_str(text.text) else {
+            return false;
+        };
+        let mut spans = vec![];
+        match tokens.into_iter().next() {
+            Some(TokenTree::Group(group)) => {
+                spans.push(group.span_open());
+                token_spans(group.stream(), in_braces, &mut spans);
+                spans.push(group.span_close());
+            }
+            _ => return false,
+        }
+        spans.windows(2).any(|pair| {
+            let start = text.offset(pair[0].end());
+            let end = text.offset(pair[1].start());
+            !text.text[start..end].trim().is_empty()
+        })
+    }
+
+    /// The offsets of the start of a macro's opening delimiter and the end of
+    /// its closing one.
+    fn delimiters(&self, mac: &Macro) -> (usize, usize) {
+        let span = mac.delimiter_span();
+        (self.offset(span.start()), self.offset(span.end()))
+    }
+}
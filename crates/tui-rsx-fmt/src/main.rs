@@ -0,0 +1,129 @@
+use clap::{Parser, ValueEnum};
+use std::{fs, path::PathBuf, process::ExitCode};
+use tui_rsx_fmt::{format_source, AttributeLayout, Config};
+use walkdir::WalkDir;
+
+/// Formats the RSX in `view!`, `mount!` and `prop!` invocations.
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// Files or directories to format. Directories are searched for `.rs`
+    /// files, skipping `target` and hidden directories.
+    #[arg(default_value = ".")]
+    paths: Vec<PathBuf>,
+
+    /// List the files that would change instead of writing them, and fail if
+    /// there are any.
+    #[arg(long)]
+    check: bool,
+
+    /// The maximum width of a line.
+    #[arg(long, default_value_t = 100)]
+    max_width: usize,
+
+    /// The number of spaces per indentation level.
+    #[arg(long, default_value_t = 4)]
+    tab_spaces: usize,
+
+    /// When to put each attribute on its own line.
+    #[arg(long, value_enum, default_value_t = Attributes::Auto)]
+    attributes: Attributes,
+
+    /// Put each attribute on its own line when an element has more than this
+    /// many, with `--attributes auto`.
+    #[arg(long)]
+    max_attributes: Option<usize>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Attributes {
+    /// When they don't fit on the line.
+    Auto,
+    /// Whenever there's more than one.
+    OnePerLine,
+    /// Never.
+    SameLine,
+}
+
+impl From<Attributes> for AttributeLayout {
+    fn from(attributes: Attributes) -> Self {
+        match attributes {
+            Attributes::Auto => AttributeLayout::Auto,
+            Attributes::OnePerLine => AttributeLayout::OnePerLine,
+            Attributes::SameLine => AttributeLayout::SameLine,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let config = Config {
+        max_width: args.max_width,
+        tab_spaces: args.tab_spaces,
+        attributes: args.attributes.into(),
+        max_attributes: args.max_attributes,
+    };
+
+    let mut failed = false;
+    for path in rust_files(&args.paths, &mut failed) {
+        let source = match fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("{}: {e}", path.display());
+                failed = true;
+                continue;
+            }
+        };
+        let formatted = match format_source(&source, &config) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                eprintln!("{}:{e}", path.display());
+                failed = true;
+                continue;
+            }
+        };
+        if formatted == source {
+            continue;
+        }
+        if args.check {
+            println!("Diff in {}", path.display());
+            failed = true;
+        } else if let Err(e) = fs::write(&path, formatted) {
+            eprintln!("{}: {e}", path.display());
+            failed = true;
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn rust_files(paths: &[PathBuf], failed: &mut bool) -> Vec<PathBuf> {
+    let mut files = vec![];
+    for path in paths {
+        let walker = WalkDir::new(path).sort_by_file_name().into_iter();
+        let entries = walker.filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            // The paths given are always searched, even if they're hidden
+            entry.depth() == 0 || !(name.starts_with('.') || name == "target")
+        });
+        for entry in entries {
+            match entry {
+                Ok(entry) if entry.file_type().is_file() => {
+                    if entry.path().extension() == Some("rs".as_ref()) {
+                        files.push(entry.into_path());
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    eprintln!("{e}");
+                    *failed = true;
+                }
+            }
+        }
+    }
+    files
+}
@@ -0,0 +1,346 @@
+use crate::{
+    contains_rsx_macro, is_rsx_macro, AttributeLayout, Config, DelimiterSpan, Error, Source,
+};
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use rstml::node::{Node, NodeAttribute};
+use syn::{spanned::Spanned, Expr, Macro, MacroDelimiter};
+
+/// Formats the body of an RSX macro, including its delimiters. `indent` is
+/// the indentation of the line the macro starts on and `column` is where its
+/// opening delimiter is.
+pub(crate) fn format_macro_body(
+    source: &Source,
+    config: &Config,
+    mac: &Macro,
+    indent: usize,
+    column: usize,
+) -> Result<String, Error> {
+    Printer { source, config }.macro_body(mac, indent, column, false)
+}
+
+struct Printer<'a> {
+    source: &'a Source<'a>,
+    config: &'a Config,
+}
+
+impl Printer<'_> {
+    /// Whether `text` fits within the max width if it starts at `column`.
+    /// Lines after the first are already indented.
+    fn fits(&self, column: usize, text: &str) -> bool {
+        text.lines().enumerate().all(|(i, line)| {
+            let start = if i == 0 { column } else { 0 };
+            start + line.chars().count() <= self.config.max_width
+        })
+    }
+
+    /// Formats a macro's body. Braced bodies are only kept on one line if the
+    /// macro is `nested` in an attribute value. Bodies with comments outside
+    /// of blocks are kept as they're written.
+    fn macro_body(
+        &self,
+        mac: &Macro,
+        indent: usize,
+        column: usize,
+        nested: bool,
+    ) -> Result<String, Error> {
+        let (open, close) = match mac.delimiter {
+            MacroDelimiter::Paren(_) => ('(', ')'),
+            MacroDelimiter::Brace(_) => ('{', '}'),
+            MacroDelimiter::Bracket(_) => ('[', ']'),
+        };
+        let span = mac.delimiter_span();
+        if self.source.has_comments(span, false) {
+            return Ok(self.original(span, indent));
+        }
+        let braced = matches!(mac.delimiter, MacroDelimiter::Brace(_));
+        let (prefix, tokens) = self.split_context(mac.tokens.clone());
+        let nodes = rstml::parse2(tokens).map_err(|e| Error::new(e.span(), &e))?;
+        if nodes.is_empty() && prefix.is_none() {
+            return Ok(format!("{open}{close}"));
+        }
+        let prefix = match prefix {
+            Some(prefix) if braced => format!(" {prefix} "),
+            Some(prefix) => format!("{prefix} "),
+            None => String::new(),
+        };
+
+        if nested || !braced {
+            let inline_column = column + 1 + prefix.len();
+            let inline: Vec<_> = nodes
+                .iter()
+                .map(|node| self.node(node, indent, inline_column))
+                .collect::<Result<_, _>>()?;
+            let inline = format!("{open}{prefix}{}{close}", inline.join(" "));
+            if !inline.contains('\n') && self.fits(column, &inline) {
+                return Ok(inline);
+            }
+        }
+
+        let body_indent = indent + self.config.tab_spaces;
+        let mut out = format!("{open}{}", prefix.trim_end());
+        for node in &nodes {
+            let node = self.node(node, body_indent, body_indent)?;
+            out.push_str(&format!("\n{:body_indent$}{node}", ""));
+        }
+        out.push_str(&format!("\n{:indent$}{close}", ""));
+        Ok(out)
+    }
+
+    /// Splits off the context argument of `view! { cx, <..> }`, as written.
+    fn split_context(&self, tokens: TokenStream) -> (Option<String>, TokenStream) {
+        let tokens: Vec<_> = tokens.into_iter().collect();
+        let comma = match tokens.first() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => None,
+            _ => tokens.iter().position(
+                |token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ','),
+            ),
+        };
+        let Some(comma) = comma else {
+            return (None, tokens.into_iter().collect());
+        };
+        let span = tokens[0].span();
+        let span = span.join(tokens[comma].span()).unwrap_or(span);
+        let prefix = self.source.slice(span).to_string();
+        (Some(prefix), tokens.into_iter().skip(comma + 1).collect())
+    }
+
+    fn node(&self, node: &Node, indent: usize, column: usize) -> Result<String, Error> {
+        match node {
+            Node::Element(element) => self.element(
+                &element.name().to_string(),
+                element.attributes(),
+                &element.children,
+                indent,
+                column,
+            ),
+            Node::Fragment(fragment) if fragment.children.is_empty() => Ok("<></>".to_string()),
+            Node::Fragment(fragment) => self.element("", &[], &fragment.children, indent, column),
+            Node::Text(text) => Ok(self.source.slice(text.value.span()).to_string()),
+            Node::Block(block) => Ok(self.block(node.span(), block.try_block(), indent)),
+            node => Ok(self.original(node.span(), indent)),
+        }
+    }
+
+    fn element(
+        &self,
+        name: &str,
+        attributes: &[NodeAttribute],
+        children: &[Node],
+        indent: usize,
+        column: usize,
+    ) -> Result<String, Error> {
+        let tab = self.config.tab_spaces;
+        let open = format!("<{name}");
+        let close = format!("</{name}>");
+        let child_indent = indent + tab;
+        // Text, or a single block, can go on the same line as the tags
+        let inline = (children.len() == 1 && !matches!(children[0], Node::Element(_)))
+            || children.iter().all(|child| matches!(child, Node::Text(_)));
+
+        if let Some(attributes) = self.inline_attributes(attributes, indent, column + open.len())? {
+            let open = format!("{open}{attributes}");
+            if children.is_empty() {
+                let tag = format!("{open}/>");
+                if self.fits(column, &tag) || attributes.is_empty() || self.same_line() {
+                    return Ok(tag);
+                }
+            } else {
+                if inline {
+                    let children: Vec<_> = children
+                        .iter()
+                        .map(|child| self.node(child, indent, column))
+                        .collect::<Result<_, _>>()?;
+                    let line = format!("{open}>{}{close}", children.join(" "));
+                    if !line.contains('\n') && self.fits(column, &line) {
+                        return Ok(line);
+                    }
+                }
+                let open = format!("{open}>");
+                if self.fits(column, &open) || attributes.is_empty() || self.same_line() {
+                    let children = self.children(children, child_indent)?;
+                    return Ok(format!("{open}\n{children}\n{:indent$}{close}", ""));
+                }
+            }
+        }
+
+        let mut out = open;
+        for attribute in attributes {
+            let attribute = self.attribute(attribute, child_indent, child_indent)?;
+            out.push_str(&format!("\n{:child_indent$}{attribute}", ""));
+        }
+        out.push_str(&format!("\n{:indent$}", ""));
+        if children.is_empty() {
+            out.push_str("/>");
+        } else {
+            let children = self.children(children, child_indent)?;
+            out.push_str(&format!(">\n{children}\n{:indent$}{close}", ""));
+        }
+        Ok(out)
+    }
+
+    fn same_line(&self) -> bool {
+        self.config.attributes == AttributeLayout::SameLine
+    }
+
+    /// The attributes on the same line as the tag, each preceded by a space,
+    /// or `None` if they should go on separate lines.
+    fn inline_attributes(
+        &self,
+        attributes: &[NodeAttribute],
+        indent: usize,
+        column: usize,
+    ) -> Result<Option<String>, Error> {
+        if attributes.is_empty() {
+            return Ok(Some(String::new()));
+        }
+        match self.config.attributes {
+            AttributeLayout::OnePerLine if attributes.len() > 1 => return Ok(None),
+            AttributeLayout::Auto
+                if self
+                    .config
+                    .max_attributes
+                    .is_some_and(|max| attributes.len() > max) =>
+            {
+                return Ok(None)
+            }
+            _ => {}
+        }
+        // Only the last attribute can span lines, like a trailing closure
+        let mut out = String::new();
+        let mut multiline = false;
+        for attribute in attributes {
+            multiline |= out.contains('\n');
+            let attribute = self.attribute(attribute, indent, column + out.len() + 1)?;
+            out.push(' ');
+            out.push_str(&attribute);
+        }
+        let wrapped = multiline || !self.fits(column, &out);
+        if wrapped && !self.same_line() {
+            return Ok(None);
+        }
+        Ok(Some(out))
+    }
+
+    /// The children of an element on their own lines, except that text is
+    /// kept on one line if there's only text and it fits.
+    fn children(&self, children: &[Node], indent: usize) -> Result<String, Error> {
+        if children.iter().all(|child| matches!(child, Node::Text(_))) {
+            let line: Vec<_> = children
+                .iter()
+                .map(|child| self.node(child, indent, indent))
+                .collect::<Result<_, _>>()?;
+            let line = line.join(" ");
+            if !line.contains('\n') && self.fits(indent, &line) {
+                return Ok(format!("{:indent$}{line}", ""));
+            }
+        }
+        let children: Vec<_> = children
+            .iter()
+            .map(|child| {
+                Ok(format!(
+                    "{:indent$}{}",
+                    "",
+                    self.node(child, indent, indent)?
+                ))
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(children.join("\n"))
+    }
+
+    fn attribute(
+        &self,
+        attribute: &NodeAttribute,
+        indent: usize,
+        column: usize,
+    ) -> Result<String, Error> {
+        let NodeAttribute::Attribute(attribute) = attribute else {
+            return Ok(self.original(attribute.span(), indent));
+        };
+        let key = attribute.key.to_string();
+        match attribute.value() {
+            Some(value) => {
+                let value = self.expr(value, indent, column + key.len() + 1)?;
+                Ok(format!("{key}={value}"))
+            }
+            None => Ok(key),
+        }
+    }
+
+    fn expr(&self, expr: &Expr, indent: usize, column: usize) -> Result<String, Error> {
+        match expr {
+            Expr::Block(block) if block.attrs.is_empty() && block.label.is_none() => {
+                Ok(self.block(block.span(), Some(&block.block), indent))
+            }
+            Expr::Macro(mac) if mac.attrs.is_empty() && is_rsx_macro(&mac.mac) => {
+                let path = format!("{}!", self.source.slice(mac.mac.path.span()));
+                let body = self.macro_body(&mac.mac, indent, column + path.len(), true)?;
+                Ok(format!("{path}{body}"))
+            }
+            expr => Ok(self.original(expr.span(), indent)),
+        }
+    }
+
+    /// Formats a block with prettyplease, unless it contains RSX that
+    /// prettyplease would mangle or comments that it would drop.
+    fn block(&self, span: Span, block: Option<&syn::Block>, indent: usize) -> String {
+        let Some(block) = block else {
+            return self.original(span, indent);
+        };
+        if contains_rsx_macro(block.to_token_stream()) || self.source.has_comments(span, true) {
+            return self.original(span, indent);
+        }
+        let file: syn::File = syn::parse_quote! {
+            fn __rsx_fmt() #block
+        };
+        let formatted = prettyplease::unparse(&file);
+        let lines: Vec<_> = formatted.trim_end().lines().collect();
+        let body = match &lines[..] {
+            [_, body @ .., _] => body,
+            _ => &[],
+        };
+        match body {
+            [] => "{}".to_string(),
+            [line] => format!("{{{}}}", line.trim_start()),
+            lines => {
+                let tab = self.config.tab_spaces;
+                let mut out = "{".to_string();
+                for line in lines {
+                    // prettyplease indents by four spaces
+                    let line = line.strip_prefix("    ").unwrap_or(line);
+                    if line.is_empty() {
+                        out.push('\n');
+                    } else {
+                        let line_indent = indent + tab;
+                        out.push_str(&format!("\n{:line_indent$}{line}", ""));
+                    }
+                }
+                out.push_str(&format!("\n{:indent$}}}", ""));
+                out
+            }
+        }
+    }
+
+    /// The source of `span` as written, with its continuation lines moved
+    /// from the indentation they had to `indent`.
+    fn original(&self, span: Span, indent: usize) -> String {
+        let text = self.source.slice(span);
+        let base = self.source.line_indent(span.start());
+        let mut lines = text.lines();
+        let mut out = lines.next().unwrap_or_default().to_string();
+        for line in lines {
+            let trimmed = line.trim_start_matches(' ');
+            let relative = (line.len() - trimmed.len()).saturating_sub(base);
+            if trimmed.is_empty() {
+                out.push('\n');
+            } else {
+                out.push_str(&format!(
+                    "\n{:width$}{trimmed}",
+                    "",
+                    width = indent + relative
+                ));
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,160 @@
+use tui_rsx_fmt::{format_source, AttributeLayout, Config};
+
+fn format(source: &str, config: &Config) -> String {
+    let formatted = format_source(source, config).unwrap();
+    assert_eq!(
+        format_source(&formatted, config).unwrap(),
+        formatted,
+        "formatting isn't idempotent"
+    );
+    formatted
+}
+
+#[test]
+fn reindents_elements() {
+    let source = r#"
+fn main() {
+    let view = mount! {
+  <column>
+            <block length=3   title="a"
+               borders=Borders::ALL/>
+    <paragraph>"one"
+        "two"</paragraph>
+      </column>
+    };
+}
+"#;
+    let expected = r#"
+fn main() {
+    let view = mount! {
+        <column>
+            <block length=3 title="a" borders=Borders::ALL/>
+            <paragraph>"one" "two"</paragraph>
+        </column>
+    };
+}
+"#;
+    assert_eq!(format(source, &Config::default()), expected);
+}
+
+#[test]
+fn wraps_attributes() {
+    let source = r#"
+fn main() {
+    let view = view! { <table header=prop!(<row style=prop!(<style fg=Color::Yellow/>)>"Col1" "Col2"</row>) column_spacing=1/> };
+}
+"#;
+    let expected = r#"
+fn main() {
+    let view = view! {
+        <table
+            header=prop!(<row style=prop!(<style fg=Color::Yellow/>)>"Col1" "Col2"</row>)
+            column_spacing=1
+        />
+    };
+}
+"#;
+    assert_eq!(format(source, &Config::default()), expected);
+
+    let config = Config {
+        max_width: 60,
+        attributes: AttributeLayout::OnePerLine,
+        ..Config::default()
+    };
+    let expected = r#"
+fn main() {
+    let view = view! {
+        <table
+            header=prop!(
+                <row style=prop!(<style fg=Color::Yellow/>)>
+                    "Col1" "Col2"
+                </row>
+            )
+            column_spacing=1
+        />
+    };
+}
+"#;
+    assert_eq!(format(source, &config), expected);
+}
+
+#[test]
+fn formats_blocks() {
+    let source = r#"
+fn main() {
+    let view = mount! { cx,
+        <column on_key={let text=text.clone();move |key| text.borrow_mut().push(key)}>
+            {   children   }
+            {view!(<paragraph>"nested"</paragraph>)}
+        </column>
+    };
+}
+"#;
+    let expected = r#"
+fn main() {
+    let view = mount! { cx,
+        <column on_key={
+            let text = text.clone();
+            move |key| text.borrow_mut().push(key)
+        }>
+            {children}
+            {view!(<paragraph>"nested"</paragraph>)}
+        </column>
+    };
+}
+"#;
+    assert_eq!(format(source, &Config::default()), expected);
+}
+
+#[test]
+fn keeps_comments() {
+    // A comment between nodes leaves the whole macro as it's written
+    let source = r#"
+fn main() {
+    let view = mount! {
+      <column>
+            // The title
+            <block length=3   title="a"/>
+            /* The body */ <paragraph>"http://example.com"</paragraph>
+      </column>
+    };
+}
+"#;
+    assert_eq!(format(source, &Config::default()), source);
+
+    // A comment in a block only leaves that block as it's written
+    let source = r#"
+fn main() {
+    let view = mount! {
+  <column on_key={
+      // Typed text is kept
+      let text=text.clone();
+      move |key| text.borrow_mut().push(key) /* in order */
+  }>
+            <paragraph>"// not a comment"</paragraph>
+      </column>
+    };
+}
+"#;
+    let expected = r#"
+fn main() {
+    let view = mount! {
+        <column on_key={
+            // Typed text is kept
+            let text=text.clone();
+            move |key| text.borrow_mut().push(key) /* in order */
+        }>
+            <paragraph>"// not a comment"</paragraph>
+        </column>
+    };
+}
+"#;
+    assert_eq!(format(source, &Config::default()), expected);
+}
+
+#[test]
+fn reports_errors() {
+    let source = "fn main() {\n    view! { <column></row> };\n}\n";
+    let error = format_source(source, &Config::default()).unwrap_err();
+    assert_eq!(error.line, 2);
+}
@@ -0,0 +1,139 @@
+use proc_macro2::{Span, TokenStream};
+use proc_macro_error::abort;
+use quote::ToTokens;
+use syn::{parse_quote, Block, Expr, ExprIf, Ident, Stmt};
+
+/// Returns the statements of a `{}` child, with any trailing `if` or `match`
+/// whose arms produce `view!`s rewritten so that each arm is wrapped in its
+/// own variant of an `EitherN` enum. The arms can then have different view
+/// types without boxing.
+pub(crate) fn lower_block(block: &Block) -> TokenStream {
+    let mut block = block.clone();
+    if let Some(tail) = tail_expr(&mut block) {
+        lower_branches(tail);
+    }
+    block.stmts.iter().map(|s| s.to_token_stream()).collect()
+}
+
+fn lower_branches(expr: &mut Expr) {
+    match expr {
+        Expr::If(expr_if) => {
+            let (arms, has_else) = if_arms(expr_if);
+            if !arms.iter().any(|arm| ends_with_view(arm)) {
+                return;
+            }
+            // A missing `else` renders nothing
+            let count = arms.len() + usize::from(!has_else);
+            let either = either_ident(&*expr_if, count);
+            let mut index = 0;
+            wrap_if(expr_if, &either, &mut index);
+        }
+        Expr::Match(expr_match) => {
+            let has_view = expr_match.arms.iter().any(|arm| match arm.body.as_ref() {
+                Expr::Block(body) => ends_with_view(&body.block),
+                body => is_view_macro(body),
+            });
+            if !has_view || expr_match.arms.len() < 2 {
+                return;
+            }
+            let either = either_ident(&*expr_match, expr_match.arms.len());
+            for (arm, index) in expr_match.arms.iter_mut().zip(0..) {
+                let variant = variant_ident(index);
+                match arm.body.as_mut() {
+                    Expr::Block(body) => wrap_block(&mut body.block, &either, &variant),
+                    body => {
+                        *body = parse_quote!(#either::#variant(#body));
+                        arm.comma = Some(Default::default());
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn either_ident(expr: &impl ToTokens, count: usize) -> Ident {
+    if count > 8 {
+        abort!(expr, "branching views support at most 8 arms, as many as `Either8` has";
+            help = "move some of the arms into a component");
+    }
+    Ident::new(&format!("Either{count}"), Span::call_site())
+}
+
+fn variant_ident(index: u8) -> Ident {
+    Ident::new(&char::from(b'A' + index).to_string(), Span::call_site())
+}
+
+fn if_arms(expr_if: &ExprIf) -> (Vec<&Block>, bool) {
+    let mut arms = vec![&expr_if.then_branch];
+    match expr_if.else_branch.as_ref().map(|(_, e)| e.as_ref()) {
+        Some(Expr::If(else_if)) => {
+            let (rest, has_else) = if_arms(else_if);
+            arms.extend(rest);
+            (arms, has_else)
+        }
+        Some(Expr::Block(else_block)) => {
+            arms.push(&else_block.block);
+            (arms, true)
+        }
+        _ => (arms, false),
+    }
+}
+
+fn wrap_if(expr_if: &mut ExprIf, either: &Ident, index: &mut u8) {
+    wrap_block(&mut expr_if.then_branch, either, &variant_ident(*index));
+    *index += 1;
+    match expr_if.else_branch.as_mut().map(|(_, e)| e.as_mut()) {
+        Some(Expr::If(else_if)) => wrap_if(else_if, either, index),
+        Some(Expr::Block(else_block)) => {
+            wrap_block(&mut else_block.block, either, &variant_ident(*index))
+        }
+        _ => {
+            let variant = variant_ident(*index);
+            expr_if.else_branch =
+                Some((Default::default(), parse_quote!({ #either::#variant(()) })));
+        }
+    }
+}
+
+fn wrap_block(block: &mut Block, either: &Ident, variant: &Ident) {
+    match tail_expr(block) {
+        Some(tail) => *tail = parse_quote!(#either::#variant(#tail)),
+        None => block
+            .stmts
+            .push(Stmt::Expr(parse_quote!(#either::#variant(())), None)),
+    }
+}
+
+/// The trailing expression of a block. A trailing `view!(..)` parses as a
+/// statement macro, so it's converted into an expression first.
+fn tail_expr(block: &mut Block) -> Option<&mut Expr> {
+    if let Some(Stmt::Macro(stmt)) = block.stmts.last() {
+        if stmt.semi_token.is_none() {
+            let mac = stmt.mac.clone();
+            *block.stmts.last_mut().expect("checked above") = Stmt::Expr(parse_quote!(#mac), None);
+        }
+    }
+    match block.stmts.last_mut() {
+        Some(Stmt::Expr(expr, None)) => Some(expr),
+        _ => None,
+    }
+}
+
+fn ends_with_view(block: &Block) -> bool {
+    match block.stmts.last() {
+        Some(Stmt::Macro(stmt)) => stmt.semi_token.is_none() && is_view_path(&stmt.mac.path),
+        Some(Stmt::Expr(expr, None)) => is_view_macro(expr),
+        _ => false,
+    }
+}
+
+fn is_view_macro(expr: &Expr) -> bool {
+    matches!(expr, Expr::Macro(mac) if is_view_path(&mac.mac.path))
+}
+
+fn is_view_path(path: &syn::Path) -> bool {
+    path.segments
+        .last()
+        .is_some_and(|segment| segment.ident == "view")
+}
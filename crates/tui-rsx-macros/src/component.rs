@@ -184,8 +184,9 @@ impl ToTokens for Model {
         }
 
         body.sig.ident = format_ident!("__{}", body.sig.ident);
-        body.sig.inputs.push(syn::parse_quote!(__parent_id: u32));
+        body.sig.inputs.push(syn::parse_quote!(__parent_id: u64));
         body.sig.output = syn::parse_quote!(-> impl LazyView<#view_type>);
+        let crate_import = get_import();
         #[allow(clippy::redundant_clone)] // false positive
         let body_name = body.sig.ident.clone();
 
@@ -230,7 +231,6 @@ impl ToTokens for Model {
 
         let component_fn_prop_docs = generate_component_fn_prop_docs(&props);
 
-        let crate_import = get_import();
         let component = quote! {
             #crate_import::LazyViewWrapper::new(#body_name(#scope_name, #used_prop_names __caller_id))
         };
@@ -261,6 +261,24 @@ impl ToTokens for Model {
             }
         };
 
+        let component_path = LitStr::new(&name.to_string(), name.span());
+        let mounted = quote! {
+            ::std::rc::Rc::new(::std::cell::RefCell::new(#crate_import::lifecycle::Mounted::new(
+                #crate_import::lifecycle::Key::new::<#crate_import::KeyWrapper<#view_type>>(
+                    concat!(module_path!(), "::", #component_path),
+                    __caller_id,
+                ),
+                move || {
+                    let _evicted = #cache_name.with(|c| {
+                        c.borrow_mut()
+                            .get_mut::<#crate_import::KeyWrapper<#view_type>>()
+                            .and_then(|map| map.remove(&__caller_id))
+                    });
+                },
+                || #component,
+            )))
+        };
+
         let widget_cache_impl = quote! {
             #cache_name.with(|c| {
                 let mut cache_mut = c.borrow_mut();
@@ -268,13 +286,13 @@ impl ToTokens for Model {
                     if let Some(cache) = map.get(&__caller_id) {
                         cache.clone()
                     } else {
-                        let res = ::std::rc::Rc::new(::std::cell::RefCell::new(#component));
+                        let res = #mounted;
                         map.insert(__caller_id, res.clone());
                         res
                     }
                 } else {
-                    let mut map = ::std::collections::HashMap::<u32, ::std::rc::Rc<::std::cell::RefCell<dyn View<#view_type>>>>::new();
-                    let res = ::std::rc::Rc::new(::std::cell::RefCell::new(#component));
+                    let mut map = ::std::collections::HashMap::<u64, ::std::rc::Rc<::std::cell::RefCell<dyn View<#view_type>>>>::new();
+                    let res = #mounted;
                     map.insert(__caller_id, res.clone());
                     cache_mut.insert::<#crate_import::KeyWrapper<#view_type>>(map);
                     res
@@ -0,0 +1,169 @@
+use proc_macro2::{Ident, Span};
+use proc_macro_error::abort;
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+/// The attributes every element accepts, handled by the macro rather than
+/// passed to the element's props.
+pub(crate) const STANDARD_ATTRS: &[&str] = &[
+    "min",
+    "max",
+    "percentage",
+    "length",
+    "state",
+    "key",
+    "on_key",
+    "on_click",
+    "on_scroll",
+    "focusable",
+    "tab_index",
+    "focused_style",
+];
+
+/// The tags the macro handles itself rather than rendering as a widget.
+pub(crate) const LAYOUT_TAGS: &[&str] = &[
+    "row",
+    "column",
+    "overlay",
+    "grid",
+    "scroll_view",
+    "show",
+    "switch",
+    "for",
+    "case",
+];
+
+/// The widgets tui-rsx provides, which its prelude brings into scope.
+pub(crate) const WIDGET_TAGS: &[&str] = &[
+    "block",
+    "paragraph",
+    "list",
+    "tabs",
+    "table",
+    "gauge",
+    "line_gauge",
+    "sparkline",
+    "bar_chart",
+    "chart",
+    "clear",
+    "canvas",
+    "stateful_list",
+    "stateful_table",
+    "stateful_scrollbar",
+];
+
+/// The closest of `candidates` to `name`, if it's close enough to be a typo.
+pub(crate) fn did_you_mean<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The `help` for an unknown name, suggesting the closest candidate or else
+/// listing them all.
+pub(crate) fn suggest(name: &str, candidates: &[&str]) -> String {
+    match did_you_mean(name, candidates.iter().copied()) {
+        Some(candidate) => format!("did you mean `{candidate}`?"),
+        None => {
+            let candidates: Vec<_> = candidates.iter().map(|c| format!("`{c}`")).collect();
+            format!("expected one of {}", candidates.join(", "))
+        }
+    }
+}
+
+/// Whether `name` is a single edit away from `candidate`.
+pub(crate) fn is_single_edit(name: &str, candidate: &str) -> bool {
+    edit_distance(name, candidate) == 1
+}
+
+/// Creates an ident for a tag or attribute name, which rstml allows to
+/// contain characters such as `-` that an ident can't.
+pub(crate) fn ident(name: &str, span: Span) -> Ident {
+    if !is_ident(name) {
+        let fixed: String = name
+            .chars()
+            .enumerate()
+            .map(|(i, c)| match i {
+                0 if is_xid_start(c) => c,
+                1.. if is_xid_continue(c) => c,
+                _ => '_',
+            })
+            .collect();
+        abort!(span, "`{}` isn't a valid name", name; help = "did you mean `{}`?", fixed);
+    }
+    Ident::new(name, span)
+}
+
+/// Whether `name` can be used as an ident.
+pub(crate) fn is_ident(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c == '_' || is_xid_start(c)) && chars.all(is_xid_continue)
+}
+
+/// The edit distance between two strings, counting a swap of adjacent
+/// characters as a single edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<_> = a.chars().collect();
+    let b: Vec<_> = b.chars().collect();
+    let mut rows = vec![(0..=b.len()).collect::<Vec<_>>()];
+    for i in 1..=a.len() {
+        let mut row = vec![i];
+        for j in 1..=b.len() {
+            let prev = &rows[i - 1];
+            let mut distance = (prev[j - 1] + usize::from(a[i - 1] != b[j - 1]))
+                .min(prev[j] + 1)
+                .min(row[j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            row.push(distance);
+        }
+        rows.push(row);
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distance_counts_edits() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("title", "title"), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("colum", "column"), 1);
+        assert_eq!(edit_distance("paragraf", "paragraph"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        // A swap of adjacent characters is a single edit
+        assert_eq!(edit_distance("titel", "title"), 1);
+        assert_eq!(edit_distance("höhe", "hhöe"), 1);
+    }
+
+    #[test]
+    fn did_you_mean_picks_closest() {
+        let tags = ["column", "overlay", "scroll_view", "switch"];
+        assert_eq!(did_you_mean("collumn", tags), Some("column"));
+        assert_eq!(did_you_mean("swich", tags), Some("switch"));
+        assert_eq!(did_you_mean("scrol_view", tags), Some("scroll_view"));
+        assert_eq!(did_you_mean("paragraph", tags), None);
+        // Short names only allow a single edit
+        assert_eq!(did_you_mean("ab", ["abc", "xyz"]), Some("abc"));
+        assert_eq!(did_you_mean("ab", ["bcd"]), None);
+        assert_eq!(did_you_mean("titel", ["title", "titles"]), Some("title"));
+    }
+
+    #[test]
+    fn is_single_edit_rejects_exact_and_distant() {
+        assert!(is_single_edit("lenght", "length"));
+        assert!(is_single_edit("on_clik", "on_click"));
+        assert!(!is_single_edit("length", "length"));
+        assert!(!is_single_edit("on_tick", "on_click"));
+    }
+}
@@ -2,21 +2,20 @@ use proc_macro::TokenStream;
 use proc_macro_crate::{crate_name, FoundCrate};
 use proc_macro_error::proc_macro_error;
 use quote::ToTokens;
-use syn::{parse_macro_input, DeriveInput};
+use syn::{parse_macro_input, DeriveInput, ItemType};
 
+mod branch;
 mod caller_id;
 mod component;
 mod component_children;
+mod diagnostic;
 mod view;
+mod widget;
 
 #[proc_macro]
 #[proc_macro_error]
 pub fn prop(tokens: TokenStream) -> TokenStream {
-    match rstml::parse(tokens) {
-        Ok(nodes) => view::parse_named_element_children(&nodes, false),
-        Err(e) => e.to_compile_error(),
-    }
-    .into()
+    view::prop(tokens.into()).into()
 }
 
 #[proc_macro]
@@ -46,6 +45,22 @@ pub fn caller_id(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
     caller_id::parse(input).into()
 }
 
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn widget(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    let opts = parse_macro_input!(attr as widget::WidgetOpts);
+    let alias = parse_macro_input!(tokens as ItemType);
+    widget::widget(opts, alias).into()
+}
+
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn stateful_widget(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    let opts = parse_macro_input!(attr as widget::WidgetOpts);
+    let alias = parse_macro_input!(tokens as ItemType);
+    widget::stateful_widget(opts, alias).into()
+}
+
 #[proc_macro_derive(ComponentChildren, attributes(children))]
 #[proc_macro_error]
 pub fn component_children(tokens: TokenStream) -> TokenStream {
@@ -57,7 +72,9 @@ fn get_import() -> proc_macro2::TokenStream {
     let found_crate = crate_name("tui-rsx").expect("tui-rsx not found");
 
     match found_crate {
-        FoundCrate::Itself => quote::quote!(crate),
+        // Examples are also reported as the crate itself, where `crate` would
+        // refer to the example, so tui-rsx aliases itself under its own name
+        FoundCrate::Itself => quote::quote!(::tui_rsx),
         FoundCrate::Name(name) => {
             let ident = proc_macro2::Ident::new(&name, proc_macro2::Span::call_site());
             quote::quote!(#ident)
@@ -1,14 +1,21 @@
-use proc_macro2::{Ident, Span, TokenStream};
-use proc_macro_error::abort_call_site;
+use crate::{branch, diagnostic};
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use proc_macro_error::{abort, abort_call_site};
 use quote::{quote, ToTokens, TokenStreamExt};
 use rstml::node::KeyedAttribute;
 use rstml::node::{Node, NodeAttribute, NodeElement};
+use rstml::{Parser, ParserConfig};
+use std::cell::Cell;
 use std::sync::atomic::AtomicU32;
 use std::sync::atomic::Ordering;
-use syn::{Block, Expr, ExprLit, Lit, LitInt};
+use syn::{spanned::Spanned, Block, Expr, ExprLit, ExprPath, Lit, LitInt, Stmt};
 
 static NEXT_ID: AtomicU32 = AtomicU32::new(0);
 
+thread_local! {
+    static ELEMENT_INDEX: Cell<u32> = const { Cell::new(0) };
+}
+
 #[derive(Clone, Debug)]
 enum Constraint {
     Min,
@@ -21,7 +28,23 @@ enum Constraint {
 enum ViewType {
     Row(Vec<View>),
     Column(Vec<View>),
-    Overlay(Vec<View>),
+    Overlay(Vec<(Option<TokenStream>, View)>),
+    Container {
+        name: Ident,
+        children: Vec<View>,
+    },
+    ScrollView {
+        offset: Box<Expr>,
+        state: Ident,
+        height: Option<Box<Expr>>,
+        children: Vec<View>,
+    },
+    Grid {
+        rows: Box<Expr>,
+        columns: Box<Expr>,
+        gap: Option<Box<Expr>>,
+        children: Vec<(GridCell, View)>,
+    },
     Element {
         name: Ident,
         fn_name: Ident,
@@ -32,6 +55,117 @@ enum ViewType {
         fn_name: Ident,
         tokens: TokenStream,
     },
+    Show {
+        when: Box<Expr>,
+        child: Box<View>,
+        fallback: Option<Box<Expr>>,
+    },
+    Switch {
+        value: Box<Expr>,
+        cases: Vec<(Box<Expr>, View)>,
+    },
+    For {
+        each: Box<Expr>,
+        key: Option<Box<Expr>>,
+        item: Ident,
+        items: Ident,
+        child: Box<View>,
+    },
+}
+
+/// Where a child of a `<grid>` is placed. Set with its `row`, `col`,
+/// `row_span` and `col_span` attributes.
+#[derive(Clone, Debug)]
+struct GridCell {
+    row: TokenStream,
+    col: TokenStream,
+    row_span: TokenStream,
+    col_span: TokenStream,
+}
+
+impl GridCell {
+    fn area(&self) -> TokenStream {
+        let Self {
+            row,
+            col,
+            row_span,
+            col_span,
+        } = self;
+        quote!(__grid.area(#row, #col, #row_span, #col_span))
+    }
+}
+
+/// The `on_key`/`on_click`/`on_scroll` handlers of an element. They're built
+/// once alongside the element functions and cloned into each layout closure,
+/// after the element's key and caller id are bound.
+#[derive(Clone, Debug)]
+struct Interaction {
+    name: Ident,
+    setters: Vec<TokenStream>,
+    bindings: TokenStream,
+    key: Option<Ident>,
+}
+
+impl Interaction {
+    fn new(attrs: &NodeAttributes) -> Option<Self> {
+        let setters = attrs.interaction_setters();
+        if setters.is_empty() {
+            return None;
+        }
+        Some(Self {
+            name: Ident::new(
+                &format!("__ev{}", NEXT_ID.fetch_add(1, Ordering::SeqCst)),
+                Span::call_site(),
+            ),
+            setters,
+            bindings: attrs.bindings.clone(),
+            key: attrs.key_name.clone(),
+        })
+    }
+
+    fn wrap(&self, view: TokenStream) -> TokenStream {
+        let name = &self.name;
+        quote!(Interactive::new(#view, #name.clone()))
+    }
+}
+
+/// What the `debug` feature records about an element, built once alongside
+/// the element functions like its [`Interaction`].
+#[derive(Clone, Debug)]
+struct DebugInfo {
+    name: Ident,
+    tag: String,
+    constraint: String,
+    key: Option<Ident>,
+}
+
+impl DebugInfo {
+    fn new(tag: String, view: &View) -> Option<Self> {
+        if !cfg!(feature = "debug") {
+            return None;
+        }
+        let constraint = match view.constraint {
+            Constraint::Min => "min",
+            Constraint::Max => "max",
+            Constraint::Percentage => "percentage",
+            Constraint::Length => "length",
+        };
+        let val = view.constraint_val.to_token_stream().to_string();
+        Some(Self {
+            name: Ident::new(
+                &format!("__dbg{}", NEXT_ID.fetch_add(1, Ordering::SeqCst)),
+                Span::call_site(),
+            ),
+            tag,
+            constraint: format!("{constraint}={val}"),
+            key: view.interaction.as_ref().and_then(|i| i.key.clone()),
+        })
+    }
+
+    fn wrap(&self, view: TokenStream) -> TokenStream {
+        let name = &self.name;
+        quote!(Inspect::new(#view, #name.clone()))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -40,6 +174,8 @@ pub(crate) struct View {
     constraint: Constraint,
     constraint_val: Expr,
     layout_props: Option<TokenStream>,
+    interaction: Option<Interaction>,
+    debug: Option<DebugInfo>,
     create_dummy_parent: bool,
 }
 
@@ -55,12 +191,48 @@ impl View {
         }
     }
 
-    fn get_overlay_tokens(&self, children: &[View], is_child: bool) -> TokenStream {
-        let fn_clones = self.generate_fn_clones();
+    fn wrap_interaction(&self, view: TokenStream) -> TokenStream {
+        let view = match &self.interaction {
+            Some(interaction) => interaction.wrap(view),
+            None => view,
+        };
+        match &self.debug {
+            Some(debug) => debug.wrap(view),
+            None => view,
+        }
+    }
+
+    /// Renders `view` into `rect`, or returns it if it's the root.
+    fn render_into(view: TokenStream, rect: Option<TokenStream>) -> TokenStream {
+        match rect {
+            Some(rect) => quote!((#view).view(f, #rect);),
+            None => view,
+        }
+    }
+
+    fn get_overlay_tokens(
+        &self,
+        children: &[(Option<TokenStream>, View)],
+        rect: Option<TokenStream>,
+    ) -> TokenStream {
+        let fn_clones = self.generate_child_fn_clones();
         let child_tokens: Vec<_> = children
             .iter()
-            .enumerate()
-            .map(|(i, v)| v.view_to_tokens(Some(i), true))
+            .map(|(placement, v)| match &v.view_type {
+                ViewType::For { items, .. } => {
+                    let collect = v.get_for_items();
+                    quote! {
+                        #collect
+                        for (_, mut __view) in #items {
+                            __view.view(f, rect);
+                        }
+                    }
+                }
+                _ => match placement {
+                    Some(placement) => v.view_to_tokens(Some(quote!(#placement.area(rect)))),
+                    None => v.view_to_tokens(Some(quote!(rect))),
+                },
+            })
             .collect();
         let layout_tokens = quote! {
             move |f: &mut Frame<_>, rect: Rect| {
@@ -68,60 +240,272 @@ impl View {
                 #(#child_tokens)*
             }
         };
+        Self::render_into(self.wrap_interaction(layout_tokens), rect)
+    }
 
-        if is_child {
-            quote!((#layout_tokens).view(f, rect);)
-        } else {
-            layout_tokens
-        }
+    fn get_grid_tokens(&self, rect: Option<TokenStream>) -> TokenStream {
+        let ViewType::Grid {
+            rows,
+            columns,
+            gap,
+            children,
+        } = &self.view_type
+        else {
+            unreachable!("only called on <grid>");
+        };
+        let fn_clones = self.generate_child_fn_clones();
+        let gap = match gap {
+            Some(gap) => quote!(#gap),
+            None => quote!(0),
+        };
+        let child_tokens: Vec<_> = children
+            .iter()
+            .map(|(cell, child)| child.view_to_tokens(Some(cell.area())))
+            .collect();
+        let layout_tokens = quote! {
+            move |f: &mut Frame<_>, rect: Rect| {
+                #fn_clones
+                let __grid = Grid::new(rect, #rows, #columns, #gap);
+                #(#child_tokens)*
+            }
+        };
+        Self::render_into(self.wrap_interaction(layout_tokens), rect)
     }
 
-    fn get_layout_tokens(
+    /// Lays out a `<row>` or `<column>` with ratatui's `Layout`.
+    fn get_direction_tokens(
         &self,
         direction: TokenStream,
         children: &[View],
-        child_index: Option<usize>,
-        parent_is_overlay: bool,
+        rect: Option<TokenStream>,
     ) -> TokenStream {
-        let constraints: Vec<_> = children.iter().map(|c| c.get_view_constraint()).collect();
+        let layout_props = self.layout_props.clone();
+        let setup = quote!(let layout = Layout::default().direction(#direction););
+        let get_chunks = |constraints| {
+            quote! {
+                let chunks = layout
+                    .constraints(#constraints)
+                    #layout_props
+                    .split(rect);
+            }
+        };
+        self.get_layout_tokens(setup, get_chunks, quote!(), children, rect)
+    }
 
-        let child_tokens: Vec<_> = children
-            .iter()
-            .enumerate()
-            .map(|(i, v)| v.view_to_tokens(Some(i), false))
-            .collect();
+    /// Lays out a `<layout:name>` with a user defined `LayoutContainer`.
+    fn get_container_tokens(
+        &self,
+        name: &Ident,
+        children: &[View],
+        rect: Option<TokenStream>,
+    ) -> TokenStream {
         let layout_props = self.layout_props.clone();
-        let fn_clones = self.generate_fn_clones();
+        let setup = quote!(let layout = #name::default() #layout_props;);
+        let get_chunks =
+            |constraints| quote!(let chunks = split_container(&layout, rect, #constraints););
+        self.get_layout_tokens(setup, get_chunks, quote!(), children, rect)
+    }
+
+    /// Lays out a `<scroll_view>` as a column, drawn off-screen at the height
+    /// of its content and scrolled into `rect`.
+    fn get_scroll_tokens(&self, rect: Option<TokenStream>) -> TokenStream {
+        let ViewType::ScrollView {
+            state,
+            height,
+            children,
+            ..
+        } = &self.view_type
+        else {
+            unreachable!("only called on <scroll_view>");
+        };
+        let height = match height {
+            Some(height) => quote!(Some(#height)),
+            None => quote!(None),
+        };
+        let get_chunks = |constraints| {
+            quote! {
+                let __scroll_constraints: Vec<Constraint> = #constraints.into();
+                let __scroll = ScrollArea::begin(f, rect, &#state, #height, &__scroll_constraints);
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints(__scroll_constraints)
+                    .split(__scroll.area());
+            }
+        };
+        self.get_layout_tokens(
+            quote!(),
+            get_chunks,
+            quote!(__scroll.end(f);),
+            children,
+            rect,
+        )
+    }
+
+    /// Splits `rect` between `children`. `setup` creates the layout,
+    /// `get_chunks` binds `chunks` to the split of `rect` for a list of
+    /// constraints and `finish` runs after the children are rendered.
+    fn get_layout_tokens(
+        &self,
+        setup: TokenStream,
+        get_chunks: impl Fn(TokenStream) -> TokenStream,
+        finish: TokenStream,
+        children: &[View],
+        rect: Option<TokenStream>,
+    ) -> TokenStream {
+        let fn_clones = self.generate_child_fn_clones();
+
+        let has_for = children
+            .iter()
+            .any(|c| matches!(c.view_type, ViewType::For { .. }));
+        let split = if has_for {
+            // The number of chunks is only known at render time, so the
+            // constraints are collected into a vec and chunks handed out in order
+            let mut collect = vec![];
+            let mut render = vec![];
+            for child in children {
+                if let ViewType::For { items, .. } = &child.view_type {
+                    let items_tokens = child.get_for_items();
+                    collect.push(quote! {
+                        #items_tokens
+                        __constraints.extend(#items.iter().map(|(constraint, _)| *constraint));
+                    });
+                    render.push(quote! {
+                        for (_, mut __view) in #items {
+                            __view.view(f, __chunks.next().unwrap_or_default());
+                        }
+                    });
+                } else {
+                    let constraint = child.get_view_constraint();
+                    collect.push(quote!(__constraints.push(#constraint);));
+                    render.push(
+                        child.view_to_tokens(Some(quote!(__chunks.next().unwrap_or_default()))),
+                    );
+                }
+            }
+            let chunks = get_chunks(quote!(__constraints));
+            quote! {
+                let mut __constraints = Vec::new();
+                #(#collect)*
+                #chunks
+                let mut __chunks = chunks.iter().copied();
+                #(#render)*
+            }
+        } else {
+            let constraints: Vec<_> = children.iter().map(|c| c.get_view_constraint()).collect();
+            let child_tokens: Vec<_> = children
+                .iter()
+                .enumerate()
+                .map(|(i, v)| v.view_to_tokens(Some(quote!(chunks[#i]))))
+                .collect();
+            let chunks = get_chunks(quote!([#(#constraints),*]));
+            quote! {
+                #chunks
+                #(#child_tokens)*
+            }
+        };
 
         let layout_tokens = quote! {
             move |f: &mut Frame<_>, rect: Rect| {
                 #fn_clones
-                let layout = Layout::default().direction(#direction);
-                let chunks = layout
-                    .constraints([#(#constraints),*])
-                    #layout_props
-                    .split(rect);
-                #(#child_tokens)*
+                #setup
+                #split
+                #finish
             }
         };
+        Self::render_into(self.wrap_interaction(layout_tokens), rect)
+    }
 
-        if let Some(child_index) = child_index {
-            if parent_is_overlay {
-                quote!((#layout_tokens).view(f, rect);)
-            } else {
-                quote!((#layout_tokens).view(f, chunks[#child_index]);)
+    /// Builds the `(constraint, view)` pair of every item of a `<for>`. Each
+    /// item's children see a `__parent_id` derived from its key, so keyed
+    /// component state follows the item rather than its position.
+    fn get_for_items(&self) -> TokenStream {
+        let ViewType::For {
+            each,
+            key,
+            item,
+            items,
+            child,
+        } = &self.view_type
+        else {
+            unreachable!("only called on <for>");
+        };
+        let (index, key): (TokenStream, Expr) = match key {
+            Some(key) if self.debug.is_none() => {
+                (quote!(_), syn::parse_quote!(for_key(&#item, #key)))
             }
-        } else {
-            layout_tokens
+            Some(key) => (quote!(__index), syn::parse_quote!(for_key(&#item, #key))),
+            None => (quote!(__index), syn::parse_quote!(__index)),
+        };
+        let for_id = caller_id(true, Some(quote!(&(#key))));
+        let child_fns = child.generate_fns();
+        let constraint = child.get_view_constraint();
+        let view = child.view_to_tokens(None);
+        // Items are recorded by index, since the key only has to be `Hash`
+        let view = match &self.debug {
+            Some(DebugInfo { name, .. }) => {
+                quote!(Inspect::new(#view, #name.keyed(__index.to_string())))
+            }
+            None => view,
+        };
+        quote! {
+            let #items: Vec<_> = ::std::iter::IntoIterator::into_iter(#each)
+                .enumerate()
+                .map(|(#index, #item)| {
+                    let __parent_id = #for_id;
+                    #child_fns
+                    (#constraint, #view)
+                })
+                .collect();
         }
     }
 
     fn generate_fn_clones(&self) -> TokenStream {
+        let interaction = self.interaction.as_ref().map(|Interaction { name, .. }| {
+            quote! {
+                let #name = #name.clone();
+            }
+        });
+        let debug = self.debug.as_ref().map(|DebugInfo { name, .. }| {
+            quote! {
+                let #name = #name.clone();
+            }
+        });
+        // The offset is read inside the scroll view's own closure, so a parent
+        // has to clone it in
+        let state = match &self.view_type {
+            ViewType::ScrollView { state, .. } => Some(quote!(let #state = #state.clone();)),
+            _ => None,
+        };
+        let fn_clones = self.generate_child_fn_clones();
+        quote!(#interaction #debug #state #fn_clones)
+    }
+
+    /// The clones a layout's closure needs for its children. Its own
+    /// interaction and debug info are used outside of the closure, so they
+    /// mustn't be moved into it.
+    fn generate_child_fn_clones(&self) -> TokenStream {
         match &self.view_type {
-            ViewType::Row(children) | ViewType::Column(children) | ViewType::Overlay(children) => {
+            ViewType::Row(children)
+            | ViewType::Column(children)
+            | ViewType::Container { children, .. }
+            | ViewType::ScrollView { children, .. } => {
                 let child_fns: Vec<_> = children.iter().map(|c| c.generate_fn_clones()).collect();
                 quote! { #(#child_fns)* }
             }
+            ViewType::Overlay(children) => {
+                let child_fns: Vec<_> = children
+                    .iter()
+                    .map(|(_, c)| c.generate_fn_clones())
+                    .collect();
+                quote! { #(#child_fns)* }
+            }
+            ViewType::Grid { children, .. } => {
+                let child_fns: Vec<_> = children
+                    .iter()
+                    .map(|(_, c)| c.generate_fn_clones())
+                    .collect();
+                quote! { #(#child_fns)* }
+            }
             ViewType::Block { fn_name, .. } => {
                 quote! {
                     let mut #fn_name = #fn_name.clone();
@@ -132,19 +516,79 @@ impl View {
                     let mut #fn_name = #fn_name.clone();
                 }
             }
+            ViewType::Show { child, .. } => child.generate_fn_clones(),
+            ViewType::Switch { cases, .. } => {
+                let case_fns: Vec<_> = cases.iter().map(|(_, c)| c.generate_fn_clones()).collect();
+                quote! { #(#case_fns)* }
+            }
+            // Items are built while rendering, inside the parent's closure
+            ViewType::For { .. } => quote!(),
         }
     }
 
     fn generate_fns(&self) -> TokenStream {
-        match &self.view_type {
-            ViewType::Row(children) | ViewType::Column(children) | ViewType::Overlay(children) => {
+        let interaction = self.interaction.as_ref().map(
+            |Interaction {
+                 name,
+                 setters,
+                 bindings,
+                 ..
+             }| {
+                quote! {
+                    #bindings
+                    let #name = Interaction::default() #(#setters)*;
+                }
+            },
+        );
+        let debug = self.debug.as_ref().map(
+            |DebugInfo {
+                 name,
+                 tag,
+                 constraint,
+                 key,
+             }| {
+                let key = match key {
+                    Some(key) => quote!(Some(format!("{:?}", #key))),
+                    None => quote!(None),
+                };
+                quote! {
+                    let #name = ElementInfo::new(#tag, #constraint, #key);
+                }
+            },
+        );
+        let fns = match &self.view_type {
+            ViewType::Row(children)
+            | ViewType::Column(children)
+            | ViewType::Container { children, .. } => {
+                let child_fns: Vec<_> = children.iter().map(|c| c.generate_fns()).collect();
+                quote! { #(#child_fns)* }
+            }
+            ViewType::ScrollView {
+                offset,
+                state,
+                children,
+                ..
+            } => {
                 let child_fns: Vec<_> = children.iter().map(|c| c.generate_fns()).collect();
+                quote! {
+                    let #state = #offset;
+                    #(#child_fns)*
+                }
+            }
+            ViewType::Overlay(children) => {
+                let child_fns: Vec<_> = children.iter().map(|(_, c)| c.generate_fns()).collect();
+                quote! { #(#child_fns)* }
+            }
+            ViewType::Grid { children, .. } => {
+                let child_fns: Vec<_> = children.iter().map(|(_, c)| c.generate_fns()).collect();
                 quote! { #(#child_fns)* }
             }
             ViewType::Block { fn_name, tokens } => {
+                let view = self.wrap_interaction(quote! {
+                    move |f: &mut Frame<_>, chunks: Rect| #tokens.view(f, chunks)
+                });
                 quote! {
-                    let mut #fn_name = ::std::rc::Rc::new(::std::cell::RefCell::new(
-                        move |f: &mut Frame<_>, chunks: Rect| #tokens.view(f, chunks)));
+                    let mut #fn_name = ::std::rc::Rc::new(::std::cell::RefCell::new(#view));
                 }
             }
             ViewType::Element {
@@ -152,46 +596,106 @@ impl View {
                 fn_name,
                 props,
                 state,
-            } => match (props, state) {
-                (Some(props), Some(state)) => {
-                    quote! { let mut #fn_name = ::std::rc::Rc::new(::std::cell::RefCell::new(#name(#props, #state))); }
-                }
-                (Some(props), None) => {
-                    quote! { let mut #fn_name = ::std::rc::Rc::new(::std::cell::RefCell::new(#name(#props))); }
-                }
-                (_, _) => {
-                    quote! { let mut #fn_name = ::std::rc::Rc::new(::std::cell::RefCell::new(#name())); }
+            } => {
+                let view = match (props, state) {
+                    (Some(props), Some(state)) => quote!(#name(#props, #state)),
+                    (Some(props), None) => quote!(#name(#props)),
+                    (_, _) => quote!(#name()),
+                };
+                let view = self.wrap_interaction(view);
+                quote! { let mut #fn_name = ::std::rc::Rc::new(::std::cell::RefCell::new(#view)); }
+            }
+            ViewType::Show { child, .. } => child.generate_fns(),
+            ViewType::Switch { cases, .. } => {
+                let case_fns: Vec<_> = cases.iter().map(|(_, c)| c.generate_fns()).collect();
+                quote! { #(#case_fns)* }
+            }
+            ViewType::For { .. } => quote!(),
+        };
+        quote!(#interaction #debug #fns)
+    }
+
+    fn view_to_tokens(&self, rect: Option<TokenStream>) -> TokenStream {
+        match &self.view_type {
+            ViewType::Row(children) => {
+                self.get_direction_tokens(quote! {Direction::Horizontal}, children, rect)
+            }
+            ViewType::Column(children) => {
+                self.get_direction_tokens(quote! {Direction::Vertical}, children, rect)
+            }
+            ViewType::Container { name, children } => {
+                self.get_container_tokens(name, children, rect)
+            }
+            ViewType::Overlay(children) => self.get_overlay_tokens(children, rect),
+            ViewType::Grid { .. } => self.get_grid_tokens(rect),
+            ViewType::ScrollView { .. } => self.get_scroll_tokens(rect),
+            ViewType::Block { fn_name, .. } | ViewType::Element { fn_name, .. } => {
+                Self::render_into(quote!(#fn_name), rect)
+            }
+            ViewType::Show { .. } | ViewType::Switch { .. } => {
+                let branch = self.wrap_interaction(self.get_branch_tokens());
+                match rect {
+                    Some(rect) => quote!((#branch).view(f, #rect);),
+                    None => {
+                        let fn_clones = self.generate_child_fn_clones();
+                        quote! {
+                            move |f: &mut Frame<_>, rect: Rect| {
+                                #fn_clones
+                                (#branch).view(f, rect);
+                            }
+                        }
+                    }
                 }
-            },
+            }
+            ViewType::For { .. } => unreachable!("<for> is expanded by its parent layout"),
         }
     }
 
-    fn view_to_tokens(&self, child_index: Option<usize>, parent_is_overlay: bool) -> TokenStream {
+    /// Picks the branch of a `<show>` or `<switch>` to render this frame. The
+    /// branches have different types, so each is wrapped in a variant of an
+    /// `EitherN` enum rather than boxed.
+    fn get_branch_tokens(&self) -> TokenStream {
         match &self.view_type {
-            ViewType::Row(children) => self.get_layout_tokens(
-                quote! {Direction::Horizontal},
-                children,
-                child_index,
-                parent_is_overlay,
-            ),
-            ViewType::Column(children) => self.get_layout_tokens(
-                quote! {Direction::Vertical},
-                children,
-                child_index,
-                parent_is_overlay,
-            ),
-            ViewType::Overlay(children) => self.get_overlay_tokens(children, child_index.is_some()),
-            ViewType::Block { fn_name, .. } | ViewType::Element { fn_name, .. } => {
-                if let Some(child_index) = child_index {
-                    if parent_is_overlay {
-                        quote! { (#fn_name).view(f, rect); }
+            ViewType::Show {
+                when,
+                child,
+                fallback,
+            } => {
+                let child = child.view_to_tokens(None);
+                let fallback = match fallback {
+                    Some(fallback) => quote!(#fallback),
+                    None => quote!(()),
+                };
+                quote! {
+                    if #when {
+                        Either2::A(#child)
                     } else {
-                        quote! { (#fn_name).view(f, chunks[#child_index]); }
+                        Either2::B(#fallback)
+                    }
+                }
+            }
+            ViewType::Switch { value, cases } => {
+                if let [(pattern, case)] = &cases[..] {
+                    let case = case.view_to_tokens(None);
+                    return quote! {
+                        match #value {
+                            #pattern => #case,
+                        }
+                    };
+                }
+                let either = Ident::new(&format!("Either{}", cases.len()), Span::call_site());
+                let arms = cases.iter().zip('A'..).map(|((pattern, case), variant)| {
+                    let variant = Ident::new(&variant.to_string(), Span::call_site());
+                    let case = case.view_to_tokens(None);
+                    quote!(#pattern => #either::#variant(#case),)
+                });
+                quote! {
+                    match #value {
+                        #(#arms)*
                     }
-                } else {
-                    quote! { (#fn_name) }
                 }
             }
+            _ => unreachable!("only called on <show> and <switch>"),
         }
     }
 }
@@ -199,12 +703,17 @@ impl View {
 impl ToTokens for View {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let fns = self.generate_fns();
-        let view = self.view_to_tokens(None, false);
+        let view = self.view_to_tokens(None);
         let dummy_parent = if self.create_dummy_parent {
             quote!(let __parent_id = 0;)
         } else {
             quote!()
         };
+        let view = if self.create_dummy_parent {
+            quote!(RootView::new(#view))
+        } else {
+            view
+        };
         tokens.append_all(quote! {
             {
                 #dummy_parent
@@ -221,6 +730,12 @@ struct NodeAttributes {
     props: Option<TokenStream>,
     state: Option<TokenStream>,
     key: Option<Expr>,
+    key_name: Option<Ident>,
+    caller_id: TokenStream,
+    bindings: TokenStream,
+    events: Vec<TokenStream>,
+    focusable: bool,
+    focus: Vec<TokenStream>,
 }
 
 impl NodeAttributes {
@@ -228,64 +743,121 @@ impl NodeAttributes {
         cx_name: Option<&TokenStream>,
         element: &NodeElement,
         children: TokenStream,
+        setters: &[TokenStream],
         object_suffix: &str,
         include_parent_id: bool,
     ) -> Self {
+        let name = diagnostic::ident(&element.name().to_string(), element.name().span());
         Self::from_nodes(
             cx_name,
-            Some(&snake_case_to_pascal_case(&element.name().to_string())),
+            Some(&Ident::new(
+                &snake_case_to_pascal_case(&name.to_string()),
+                name.span(),
+            )),
             element.attributes(),
             if children.is_empty() {
                 None
             } else {
                 Some(children)
             },
+            setters,
             object_suffix,
             include_parent_id,
         )
     }
 
     fn parse_standard_attrs(&mut self, attribute: &KeyedAttribute) -> bool {
-        match attribute.key.to_string().as_str() {
-            "min" => {
-                self.constraint = Constraint::Min;
-                self.expr = attribute.value().unwrap().clone();
-                true
-            }
-            "max" => {
-                self.constraint = Constraint::Max;
-                self.expr = attribute.value().unwrap().clone();
+        let name = attribute.key.to_string();
+        let constraint = match name.as_str() {
+            "min" => Some(Constraint::Min),
+            "max" => Some(Constraint::Max),
+            "percentage" => Some(Constraint::Percentage),
+            "length" => Some(Constraint::Length),
+            _ => None,
+        };
+        if let Some(constraint) = constraint {
+            self.constraint = constraint;
+            self.expr = required_value(attribute).clone();
+            return true;
+        }
+        match name.as_str() {
+            "state" => {
+                if let Some(val) = &attribute.value() {
+                    self.state = Some(val.to_token_stream());
+                }
                 true
             }
-            "percentage" => {
-                self.constraint = Constraint::Percentage;
-                self.expr = attribute.value().unwrap().clone();
+            "key" => {
+                self.key = Some(required_value(attribute).clone());
                 true
             }
-            "length" => {
-                self.constraint = Constraint::Length;
-                self.expr = attribute.value().unwrap().clone();
+            "on_key" | "on_click" | "on_scroll" => {
+                let handler = Ident::new(&name, attribute.key.span());
+                let val = setter_arg(required_value(attribute));
+                self.events.push(quote!(.#handler(#val)));
                 true
             }
-            "state" => {
-                if let Some(val) = &attribute.value() {
-                    self.state = Some(val.to_token_stream());
+            "focusable" => {
+                if let Some(val) = attribute.value() {
+                    abort!(val, "`focusable` doesn't take a value"; help = "use `tab_index` to change the tab order");
                 }
+                self.focusable = true;
                 true
             }
-            "key" => {
-                self.key = Some(attribute.value().unwrap().clone());
+            "tab_index" | "focused_style" => {
+                let setter = Ident::new(&name, attribute.key.span());
+                let val = setter_arg(required_value(attribute));
+                self.focusable = true;
+                self.focus.push(quote!(.#setter(#val)));
                 true
             }
             _ => false,
         }
     }
 
+    /// The setters applied to the element's `Interaction`, if it has any
+    /// handlers, is focusable or has a `key`. Focus is keyed by the element's
+    /// caller id.
+    fn interaction_setters(&self) -> Vec<TokenStream> {
+        let mut setters = vec![];
+        if self.focusable {
+            let caller_id = &self.caller_id;
+            setters.push(quote!(.focusable(#caller_id)));
+            setters.extend(self.focus.iter().cloned());
+        }
+        setters.extend(self.events.iter().cloned());
+        if let Some(key) = &self.key_name {
+            setters.push(quote!(.key(#key)));
+        }
+        setters
+    }
+
+    /// Sets the element's caller id. A `key` and the id of a focusable
+    /// element are used by both the props and the `Interaction`, so they're
+    /// evaluated once in `bindings` and referred to by name.
+    fn bind_caller_id(&mut self, include_parent_id: bool) {
+        let index = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        self.key_name = self.key.as_ref().map(|key| {
+            let name = Ident::new(&format!("__key{index}"), Span::call_site());
+            self.bindings.extend(quote!(let #name = &(#key);));
+            name
+        });
+        let key = self.key_name.as_ref().map(|key| quote!(#key));
+        self.caller_id = caller_id(include_parent_id, key);
+        if self.focusable {
+            let name = Ident::new(&format!("__id{index}"), Span::call_site());
+            let id = &self.caller_id;
+            self.bindings.extend(quote!(let #name = #id;));
+            self.caller_id = quote!(#name);
+        }
+    }
+
     fn from_nodes(
         cx_name: Option<&TokenStream>,
-        tag_name: Option<&str>,
+        tag_name: Option<&Ident>,
         nodes: &[NodeAttribute],
         args: Option<TokenStream>,
+        setters: &[TokenStream],
         object_suffix: &str,
         include_parent_id: bool,
     ) -> Self {
@@ -295,6 +867,12 @@ impl NodeAttributes {
             props: None,
             state: None,
             key: None,
+            key_name: None,
+            caller_id: TokenStream::default(),
+            bindings: TokenStream::default(),
+            events: vec![],
+            focusable: false,
+            focus: vec![],
         };
 
         let custom_attrs: Vec<_> = nodes
@@ -308,76 +886,59 @@ impl NodeAttributes {
                 None
             })
             .collect();
+        attrs.bind_caller_id(include_parent_id);
 
-        for attribute in &custom_attrs {
-            let func_name = Ident::new(&attribute.key.to_string(), Span::call_site());
-            if let Some(tag_name) = tag_name {
-                let val = if let Some(val) = &attribute.value() {
+        if let Some(tag_name) = tag_name {
+            let custom_setters = custom_attrs.iter().map(|attribute| {
+                check_unknown_attr(attribute);
+                let func_name = diagnostic::ident(&attribute.key.to_string(), attribute.key.span());
+                let val = if let Some(val) = attribute.value() {
+                    let val = setter_arg(val);
                     quote!(#val)
                 } else {
                     quote!()
                 };
+                quote!(.#func_name(#val))
+            });
+            let props = build_struct(tag_name, &args, object_suffix, &attrs.caller_id);
+            let props = quote!(#props #(#custom_setters)* #(#setters)* .build());
 
-                if let Some(props) = attrs.props {
-                    attrs.props = Some(quote! {
-                        #props.#func_name(#val)
-                    });
-                } else {
-                    let props = build_struct(
-                        tag_name,
-                        &args,
-                        object_suffix,
-                        include_parent_id,
-                        attrs.key.clone(),
-                    );
-                    if let Some(cx_name) = cx_name {
-                        attrs.props = Some(quote! { #cx_name.clone(), #props.#func_name(#val) });
-                    } else {
-                        attrs.props = Some(quote! { #props.#func_name(#val) });
-                    }
-                }
-            }
-        }
-
-        if let Some(props) = &attrs.props {
-            attrs.props = Some(quote! { #props.build() });
-        }
-
-        if let Some(tag_name) = tag_name {
-            if custom_attrs.is_empty() {
-                let props = build_struct(
-                    tag_name,
-                    &args,
-                    object_suffix,
-                    include_parent_id,
-                    attrs.key.clone(),
-                );
-                if let Some(cx_name) = cx_name {
-                    attrs.props = Some(quote! { #cx_name.clone(), #props.build() });
-                } else {
-                    attrs.props = Some(quote! { #props.build() });
-                }
+            if let Some(cx_name) = cx_name {
+                attrs.props = Some(quote! { #cx_name.clone(), #props });
+            } else if attrs.bindings.is_empty() {
+                attrs.props = Some(props);
+            } else {
+                // Not part of a view, so nothing else emits the bindings
+                let bindings = &attrs.bindings;
+                attrs.props = Some(quote!({ #bindings #props }));
             }
         }
 
         attrs
     }
 
-    fn from_layout_nodes(nodes: &[NodeAttribute]) -> Self {
+    fn from_layout_nodes(nodes: &[NodeAttribute], include_parent_id: bool) -> Self {
         let mut attrs = Self {
             constraint: Constraint::Min,
             expr: get_default_constraint(),
             props: None,
             state: None,
             key: None,
+            key_name: None,
+            caller_id: TokenStream::default(),
+            bindings: TokenStream::default(),
+            events: vec![],
+            focusable: false,
+            focus: vec![],
         };
 
-        // let mut attribute_parsed = false;
         for node in nodes {
             if let NodeAttribute::Attribute(attribute) = node {
                 if !attrs.parse_standard_attrs(attribute) {
-                    let func_name = Ident::new(&attribute.key.to_string(), Span::call_site());
-                    if let Some(val) = &attribute.value() {
+                    let func_name =
+                        diagnostic::ident(&attribute.key.to_string(), attribute.key.span());
+                    if let Some(val) = attribute.value() {
+                        let val = setter_arg(val);
                         if let Some(props) = attrs.props {
                             attrs.props = Some(quote! {
                                 #props.#func_name(#val)
@@ -389,29 +950,40 @@ impl NodeAttributes {
                 }
             }
         }
+        attrs.bind_caller_id(include_parent_id);
 
         attrs
     }
 }
 
+/// Identifies an element by the macro call site, its position within the
+/// invocation, its parent and a reference to its `key`, so ids don't depend on
+/// expansion order.
+fn caller_id(include_parent_id: bool, key: Option<TokenStream>) -> TokenStream {
+    let index = ELEMENT_INDEX.with(|i| i.replace(i.get() + 1)).to_string();
+    let parent_id = if include_parent_id {
+        quote!(__parent_id)
+    } else {
+        quote!(0)
+    };
+    let key = key.unwrap_or_else(|| quote!(&()));
+    quote! {
+        derive_caller_id(
+            concat!(module_path!(), "::", file!(), ":", line!(), ":", column!(), "#", #index),
+            #parent_id,
+            #key,
+        )
+    }
+}
+
 fn build_struct(
-    tag_name: &str,
+    tag_name: &Ident,
     args: &Option<TokenStream>,
     object_suffix: &str,
-    include_parent_id: bool,
-    key: Option<Expr>,
+    caller_id_args: &TokenStream,
 ) -> TokenStream {
-    let object = capitalize(tag_name) + object_suffix;
-    let ident = Ident::new(&object, Span::call_site());
-    let caller_id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
-    let key_clause = key.map(|k| quote!(+ &#k.to_string()));
-    let caller_id_args = if include_parent_id {
-        quote!((__parent_id.to_string() + &#caller_id.to_string() #key_clause).parse().expect("invalid integer"))
-    } else if key_clause.is_some() {
-        quote!((#caller_id.to_string() #key_clause).parse().expect("invalid integer"))
-    } else {
-        quote!(#caller_id)
-    };
+    let object = capitalize(&tag_name.to_string()) + object_suffix;
+    let ident = Ident::new(&object, tag_name.span());
     if let Some(args) = args.as_ref() {
         quote! {
             #ident::new(#args).__caller_id(#caller_id_args)
@@ -424,30 +996,237 @@ fn build_struct(
 }
 
 pub(crate) fn view(tokens: TokenStream, include_parent_id: bool) -> TokenStream {
+    ELEMENT_INDEX.with(|i| i.set(0));
     let mut tokens = tokens.into_iter().peekable();
-    let cx_token = if tokens.peek().unwrap().to_string() != "<" {
-        let token = tokens.next().unwrap().to_token_stream();
-        let _comma = tokens.next().unwrap();
-        token
-    } else {
-        quote! { () }
+    let cx_token = match tokens.peek() {
+        None => {
+            abort_call_site!("expected an element"; help = "try `view! { <column></column> }`")
+        }
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => quote! { () },
+        Some(_) => {
+            let token = tokens.next().unwrap();
+            match tokens.next() {
+                Some(TokenTree::Punct(punct)) if punct.as_char() == ',' => {}
+                Some(other) => abort!(other, "expected `,` after the context"),
+                None => abort!(token, "expected `,` and an element after the context"),
+            }
+            token.to_token_stream()
+        }
     };
 
-    match rstml::parse2(tokens.collect()) {
-        Ok(nodes) => {
-            let mut view = parse_root_nodes(&cx_token, nodes, include_parent_id);
-            view.create_dummy_parent = !include_parent_id;
-            view.to_token_stream()
+    let (nodes, errors) = parse_rsx(tokens.collect());
+    if !errors.is_empty() {
+        return partial_expansion(errors, hints(&hint_nodes(&nodes)));
+    }
+    let mut view = parse_root_nodes(&cx_token, nodes, include_parent_id);
+    view.create_dummy_parent = !include_parent_id;
+    view.to_token_stream()
+}
+
+/// Parses RSX, recovering from errors so the nodes that did parse are still
+/// returned along with the errors as `compile_error!`s.
+fn parse_rsx(tokens: TokenStream) -> (Vec<Node>, Vec<TokenStream>) {
+    let config = ParserConfig::new().recover_block(true);
+    let (nodes, errors) = Parser::new(config).parse_recoverable(tokens).split_vec();
+    let errors = errors
+        .into_iter()
+        .map(|error| error.emit_as_expr_tokens())
+        .collect();
+    (nodes, errors)
+}
+
+/// The expansion of RSX that failed to parse. `hints` is never run, but it
+/// lets rust-analyzer resolve element names and complete attributes in the
+/// parts that did parse while the view is being written.
+fn partial_expansion(errors: Vec<TokenStream>, hints: TokenStream) -> TokenStream {
+    quote! {{
+        #(#errors;)*
+        #hints
+        ::core::unreachable!()
+    }}
+}
+
+/// Drops what would abort the partial expansion rather than leave it out:
+/// elements and attributes whose names aren't idents yet, and attributes
+/// missing the value they require, as in `<block length` while it's typed.
+/// Widgets with a child block that didn't parse are dropped too, since
+/// their props can't be built without it.
+fn hint_nodes(nodes: &[Node]) -> Vec<Node> {
+    nodes
+        .iter()
+        .filter_map(|node| match node {
+            Node::Element(element) => hint_element(element).map(Node::Element),
+            Node::Fragment(fragment) => {
+                let mut fragment = fragment.clone();
+                fragment.children = hint_nodes(&fragment.children);
+                Some(Node::Fragment(fragment))
+            }
+            node => Some(node.clone()),
+        })
+        .collect()
+}
+
+fn hint_element(element: &NodeElement) -> Option<NodeElement> {
+    let name = element.name().to_string();
+    if !diagnostic::is_ident(name.strip_prefix("layout:").unwrap_or(&name)) {
+        return None;
+    }
+    let is_layout = name.starts_with("layout:")
+        || matches!(
+            name.as_str(),
+            "row"
+                | "column"
+                | "overlay"
+                | "grid"
+                | "scroll_view"
+                | "for"
+                | "show"
+                | "switch"
+                | "case"
+        );
+    let invalid_block = element
+        .children
+        .iter()
+        .any(|node| matches!(node, Node::Block(block) if block.try_block().is_none()));
+    if invalid_block && !is_layout {
+        return None;
+    }
+    let mut element = element.clone();
+    element.open_tag.attributes.retain(|node| {
+        let NodeAttribute::Attribute(attribute) = node else {
+            return true;
+        };
+        let key = attribute.key.to_string();
+        let (prefix, name) = key.split_once(':').unwrap_or(("", &key));
+        let needs_value = match prefix {
+            "" => {
+                (diagnostic::STANDARD_ATTRS.contains(&name) && name != "focusable")
+                    || GRID_CELL_ATTRS.contains(&name)
+            }
+            "let" => false,
+            _ => true,
+        };
+        let has_value = attribute.value().is_some();
+        let valid_value = if key == "focusable" {
+            !has_value
+        } else {
+            has_value || !needs_value
+        };
+        diagnostic::is_ident(name) && valid_value
+    });
+    element.children = hint_nodes(&element.children);
+    Some(element)
+}
+
+/// Builds the props of every widget in `nodes`, as they'd be built by the
+/// full expansion. Layout and control flow elements aren't checked, since
+/// they may be incomplete, and only their attribute values are kept.
+fn hints(nodes: &[Node]) -> TokenStream {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Element(element) => element_hints(element),
+            Node::Block(block) => match block.try_block() {
+                Some(block) => quote!(let _ = #block;),
+                None => quote!(),
+            },
+            _ => quote!(),
+        })
+        .collect()
+}
+
+fn element_hints(element: &NodeElement) -> TokenStream {
+    let name = element.name().to_string();
+    let values: Vec<_> = element
+        .attributes()
+        .iter()
+        .filter_map(|node| match node {
+            NodeAttribute::Attribute(attribute) if !attribute.key.to_string().contains(':') => {
+                attribute.value()
+            }
+            _ => None,
+        })
+        .collect();
+    let values = quote!(#(let _ = #values;)*);
+    match name.as_str() {
+        "for" => {
+            let children = hints(&element.children);
+            let item = element.attributes().iter().find_map(|node| match node {
+                NodeAttribute::Attribute(attribute) => {
+                    let binding = attribute.key.to_string().strip_prefix("let:")?.to_string();
+                    Some(diagnostic::ident(&binding, attribute.key.span()))
+                }
+                _ => None,
+            });
+            match (attribute_value(element, "each"), item) {
+                (Some(each), Some(item)) => {
+                    let key = attribute_value(element, "key").map(|key| quote!(let _ = #key;));
+                    quote! {
+                        for #item in ::std::iter::IntoIterator::into_iter(#each) {
+                            #key
+                            #children
+                        }
+                    }
+                }
+                _ => children,
+            }
+        }
+        "switch" => {
+            let cases = element.children.iter().filter_map(|node| match node {
+                Node::Element(case) => {
+                    let pattern = attribute_value(case, "pattern")?;
+                    let children = hints(&case.children);
+                    Some(quote!(#pattern => { #children }))
+                }
+                _ => None,
+            });
+            match attribute_value(element, "value") {
+                Some(value) => quote!(match #value { #(#cases,)* _ => {} }),
+                None => hints(&element.children),
+            }
+        }
+        "overlay" | "grid" => {
+            let attrs = if name == "grid" {
+                GRID_CELL_ATTRS
+            } else {
+                PLACEMENT_ATTRS
+            };
+            let children: TokenStream = element
+                .children
+                .iter()
+                .map(|node| match node {
+                    Node::Element(child) => element_hints(&take_attributes(child, attrs).1),
+                    node => hints(std::slice::from_ref(node)),
+                })
+                .collect();
+            quote!(#values #children)
+        }
+        "row" | "column" | "scroll_view" | "show" | "case" => {
+            let children = hints(&element.children);
+            quote!(#values #children)
+        }
+        name if name.starts_with("layout:") => {
+            let children = hints(&element.children);
+            quote!(#values #children)
+        }
+        _ => {
+            let (args, setters) = widget_args(element, false);
+            let attrs = NodeAttributes::from_custom(None, element, args, &setters, "Props", false);
+            let props = attrs.props;
+            quote!(let _ = #props;)
         }
-        Err(e) => e.to_compile_error(),
     }
 }
 
 fn parse_root_nodes(cx_name: &TokenStream, nodes: Vec<Node>, include_parent_id: bool) -> View {
-    if let [node] = &nodes[..] {
-        parse_root_node(cx_name, node, include_parent_id)
-    } else {
-        abort_call_site!(format!("RSX should contain a single root node"));
+    match &nodes[..] {
+        [node] => parse_root_node(cx_name, node, include_parent_id),
+        [] => abort_call_site!("RSX should contain a single root node"),
+        [_, extra, ..] => abort!(
+            extra,
+            "RSX should contain a single root node";
+            help = "wrap the nodes in a `<row>`, `<column>` or `<overlay>`"
+        ),
     }
 }
 
@@ -455,7 +1234,7 @@ fn parse_root_node(cx_name: &TokenStream, node: &Node, include_parent_id: bool)
     if let Node::Element(element) = node {
         parse_element(cx_name, element, include_parent_id)
     } else {
-        abort_call_site!("RSX root node should be a named element");
+        abort!(node, "RSX root node should be a named element");
     }
 }
 
@@ -463,35 +1242,56 @@ fn parse_elements(cx_name: &TokenStream, nodes: &[Node], include_parent_id: bool
     let mut views = vec![];
     for node in nodes {
         match node {
+            Node::Element(element) if element.name().to_string() == "for" => {
+                views.push(parse_for(cx_name, element));
+            }
             Node::Element(element) => {
                 views.push(parse_element(cx_name, element, include_parent_id));
             }
             Node::Block(block) => {
                 if let Some(block) = block.try_block() {
-                    let content = get_block_contents(block);
-                    views.push(View {
+                    let content = branch::lower_block(block);
+                    let mut view = View {
                         view_type: ViewType::Block {
                             tokens: content,
                             fn_name: Ident::new(
                                 &format!("__fn{}", NEXT_ID.fetch_add(1, Ordering::SeqCst)),
-                                Span::call_site(),
+                                block.span(),
                             ),
                         },
                         constraint: Constraint::Min,
                         constraint_val: get_default_constraint(),
                         create_dummy_parent: false,
                         layout_props: None,
-                    })
+                        interaction: None,
+                        debug: None,
+                    };
+                    view.debug = DebugInfo::new("{}".to_string(), &view);
+                    views.push(view);
                 }
             }
             node => {
-                abort_call_site!(format!("Invalid RSX node: {node:?}"));
+                abort!(node, "only elements and blocks can be placed here");
             }
         }
     }
     views
 }
 
+pub(crate) fn prop(tokens: TokenStream) -> TokenStream {
+    ELEMENT_INDEX.with(|i| i.set(0));
+    let (nodes, errors) = parse_rsx(tokens);
+    if errors.is_empty() {
+        return parse_named_element_children(&nodes, false);
+    }
+    let props = parse_named_element_children(&hint_nodes(&nodes), false);
+    if props.is_empty() {
+        partial_expansion(errors, quote!())
+    } else {
+        partial_expansion(errors, quote!(let _ = #props;))
+    }
+}
+
 pub(crate) fn parse_named_element_children(nodes: &[Node], include_parent_id: bool) -> TokenStream {
     let mut tokens = vec![];
     let mut force_vec = false;
@@ -499,8 +1299,14 @@ pub(crate) fn parse_named_element_children(nodes: &[Node], include_parent_id: bo
         match node {
             Node::Element(element) => {
                 let children = parse_named_element_children(&element.children, include_parent_id);
-                let attrs =
-                    NodeAttributes::from_custom(None, element, children, "", include_parent_id);
+                let attrs = NodeAttributes::from_custom(
+                    None,
+                    element,
+                    children,
+                    &[],
+                    "",
+                    include_parent_id,
+                );
 
                 if let Some(props) = attrs.props {
                     tokens.push(quote! { #props });
@@ -518,8 +1324,8 @@ pub(crate) fn parse_named_element_children(nodes: &[Node], include_parent_id: bo
                     tokens.push(quote! { #content });
                 }
             }
-            Node::Doctype(_) => {
-                abort_call_site!("Doctype invalid at this location");
+            Node::Doctype(doctype) => {
+                abort!(doctype, "Doctype invalid at this location");
             }
             // Node::Attribute(_) => {
             //     abort_call_site!("Attribute invalid at this location");
@@ -542,9 +1348,11 @@ pub(crate) fn parse_named_element_children(nodes: &[Node], include_parent_id: bo
 }
 
 fn parse_element(cx_name: &TokenStream, element: &NodeElement, include_parent_id: bool) -> View {
-    match element.name().to_string().as_str() {
+    let tag = element.name().to_string();
+    let mut view = match tag.as_str() {
         "row" => {
-            let attrs = NodeAttributes::from_layout_nodes(element.attributes());
+            let attrs = NodeAttributes::from_layout_nodes(element.attributes(), include_parent_id);
+            let interaction = Interaction::new(&attrs);
             let children = parse_elements(cx_name, &element.children, include_parent_id);
 
             View {
@@ -553,10 +1361,13 @@ fn parse_element(cx_name: &TokenStream, element: &NodeElement, include_parent_id
                 constraint_val: attrs.expr,
                 create_dummy_parent: false,
                 layout_props: attrs.props,
+                interaction,
+                debug: None,
             }
         }
         "column" => {
-            let attrs = NodeAttributes::from_layout_nodes(element.attributes());
+            let attrs = NodeAttributes::from_layout_nodes(element.attributes(), include_parent_id);
+            let interaction = Interaction::new(&attrs);
             let children = parse_elements(cx_name, &element.children, include_parent_id);
 
             View {
@@ -565,11 +1376,14 @@ fn parse_element(cx_name: &TokenStream, element: &NodeElement, include_parent_id
                 constraint_val: attrs.expr,
                 create_dummy_parent: false,
                 layout_props: attrs.props,
+                interaction,
+                debug: None,
             }
         }
         "overlay" => {
-            let attrs = NodeAttributes::from_layout_nodes(element.attributes());
-            let children = parse_elements(cx_name, &element.children, include_parent_id);
+            let attrs = NodeAttributes::from_layout_nodes(element.attributes(), include_parent_id);
+            let interaction = Interaction::new(&attrs);
+            let children = parse_overlay_children(cx_name, &element.children, include_parent_id);
 
             View {
                 view_type: ViewType::Overlay(children),
@@ -577,34 +1391,791 @@ fn parse_element(cx_name: &TokenStream, element: &NodeElement, include_parent_id
                 constraint_val: attrs.expr,
                 create_dummy_parent: false,
                 layout_props: attrs.props,
+                interaction,
+                debug: None,
             }
         }
-        name => {
-            let children = parse_named_element_children(&element.children, include_parent_id);
-            let attrs = NodeAttributes::from_custom(
-                Some(cx_name),
-                element,
-                children,
-                "Props",
-                include_parent_id,
-            );
+        "grid" => parse_grid(cx_name, element, include_parent_id),
+        "scroll_view" => parse_scroll_view(cx_name, element, include_parent_id),
+        name if name.starts_with("layout:") => {
+            let attrs = NodeAttributes::from_layout_nodes(element.attributes(), include_parent_id);
+            let interaction = Interaction::new(&attrs);
+            let children = parse_elements(cx_name, &element.children, include_parent_id);
+            let name = snake_case_to_pascal_case(&name["layout:".len()..]);
+
             View {
-                view_type: ViewType::Element {
-                    name: Ident::new(name, Span::call_site()),
-                    fn_name: Ident::new(
-                        &format!("__fn{}", NEXT_ID.fetch_add(1, Ordering::SeqCst)),
-                        Span::call_site(),
-                    ),
-                    props: attrs.props,
-                    state: attrs.state,
+                view_type: ViewType::Container {
+                    name: diagnostic::ident(&name, element.name().span()),
+                    children,
                 },
                 constraint: attrs.constraint,
                 constraint_val: attrs.expr,
                 create_dummy_parent: false,
-                layout_props: None,
+                layout_props: attrs.props,
+                interaction,
+                debug: None,
             }
         }
+        "for" => {
+            abort!(
+                element.name(),
+                "`<for>` can only be used inside a row, column, overlay or scroll view"
+            )
+        }
+        "show" => parse_show(cx_name, element, include_parent_id),
+        "switch" => parse_switch(cx_name, element, include_parent_id),
+        "case" => abort!(
+            element.name(),
+            "`<case>` can only be used inside a `<switch>`"
+        ),
+        _ => {
+            check_unknown_tag(element);
+            let (args, setters) = widget_args(element, include_parent_id);
+            parse_widget(cx_name, element, args, &setters, include_parent_id)
+        }
+    };
+    view.debug = DebugInfo::new(tag, &view);
+    view
+}
+
+/// The arguments passed to a widget's props constructor and any setters
+/// applied after its attributes, built from its children.
+fn widget_args(element: &NodeElement, include_parent_id: bool) -> (TokenStream, Vec<TokenStream>) {
+    match element.name().to_string().as_str() {
+        "chart" => parse_chart_children(&element.children, include_parent_id),
+        "canvas" => {
+            let paint = parse_canvas_children(&element.children, include_parent_id);
+            (TokenStream::default(), vec![paint])
+        }
+        _ => {
+            let children = parse_named_element_children(&element.children, include_parent_id);
+            (children, vec![])
+        }
+    }
+}
+
+/// Catches typos in the layout tags the macro handles itself and the widgets
+/// tui-rsx provides, which would otherwise be reported as a missing widget,
+/// often with a suggestion of the props type rather than the tag. Only
+/// snake_case names are checked, since anything else is a component. Longer
+/// forms of a tag, such as `<columns>`, are left alone since they're likely
+/// widgets of their own.
+fn check_unknown_tag(element: &NodeElement) {
+    let is_snake_case = |name: &str| {
+        name.chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    };
+    let name = element.name().to_string();
+    if let Some((prefix, rest)) = name.split_once(':') {
+        if is_snake_case(prefix) {
+            abort!(
+                element.name(),
+                "unknown tag prefix `{}:`", prefix;
+                help = "did you mean `layout:{}`?", rest
+            );
+        }
+        return;
+    }
+    if !is_snake_case(&name) {
+        return;
+    }
+    let tags = diagnostic::LAYOUT_TAGS
+        .iter()
+        .chain(diagnostic::WIDGET_TAGS)
+        .copied();
+    if let Some(tag) = diagnostic::did_you_mean(&name, tags) {
+        if !name.starts_with(tag) {
+            abort!(
+                element.name(),
+                "unknown element `<{}>`", name;
+                help = "did you mean `<{}>`?", tag
+            );
+        }
+    }
+}
+
+/// Catches typos in the standard attributes, which would otherwise be passed
+/// on to the props builder and reported as a missing method. Only a single
+/// edit is allowed and longer or shorter forms are left alone, since props of
+/// their own can look a lot like a standard attribute.
+fn check_unknown_attr(attribute: &KeyedAttribute) {
+    let key = attribute.key.to_string();
+    let Some(attr) = diagnostic::did_you_mean(&key, diagnostic::STANDARD_ATTRS.iter().copied())
+    else {
+        return;
+    };
+    if diagnostic::is_single_edit(&key, attr) && !key.starts_with(attr) && !attr.starts_with(&key) {
+        abort!(
+            attribute.key,
+            "unknown attribute `{}`", key;
+            help = "did you mean `{}`?", attr
+        );
+    }
+}
+
+/// The value of the attribute `name`, if the element has it.
+fn attribute_value(element: &NodeElement, name: &str) -> Option<Expr> {
+    element.attributes().iter().find_map(|node| match node {
+        NodeAttribute::Attribute(attribute) if attribute.key.to_string() == name => {
+            attribute.value().cloned()
+        }
+        _ => None,
+    })
+}
+
+/// The value of an attribute that can't be used without one.
+fn required_value(attribute: &KeyedAttribute) -> &Expr {
+    match attribute.value() {
+        Some(val) => val,
+        None => abort!(attribute.key, "`{}` requires a value", attribute.key),
+    }
+}
+
+/// The argument passed to a setter for an attribute's value. Closures and
+/// longer expressions have to be wrapped in braces to be used as a value,
+/// which would otherwise be linted as unnecessary once passed to the setter.
+fn setter_arg(val: &Expr) -> &Expr {
+    if let Expr::Block(block) = val {
+        if let [Stmt::Expr(expr, None)] = block.block.stmts.as_slice() {
+            if block.attrs.is_empty() && block.label.is_none() {
+                return expr;
+            }
+        }
+    }
+    val
+}
+
+/// Parses `<for each=.. key=.. let:item>`, whose single child is rendered once
+/// per item.
+fn parse_for(cx_name: &TokenStream, element: &NodeElement) -> View {
+    let mut each = None;
+    let mut key = None;
+    let mut item = None;
+    for node in element.attributes() {
+        let NodeAttribute::Attribute(attribute) = node else {
+            continue;
+        };
+        let name = attribute.key.to_string();
+        match name.as_str() {
+            "each" => each = attribute.value().cloned().map(Box::new),
+            "key" => key = attribute.value().cloned().map(Box::new),
+            _ => match name.strip_prefix("let:") {
+                Some(binding) => item = Some(diagnostic::ident(binding, attribute.key.span())),
+                None => abort!(attribute.key, "unknown `<for>` attribute `{}`", name;
+                    help = diagnostic::suggest(&name, &["each", "key", "let:<name>"])),
+            },
+        }
+    }
+    let Some(each) = each else {
+        abort!(element.name(), "`<for>` requires an `each` attribute");
+    };
+    let Some(item) = item else {
+        abort!(
+            element.name(),
+            "`<for>` requires a `let:<name>` binding for each item"
+        );
+    };
+
+    let mut children = parse_elements(cx_name, &element.children, true);
+    if children.len() != 1 {
+        abort!(element.name(), "`<for>` should contain a single child");
+    }
+    let child = children.remove(0);
+
+    let mut view = View {
+        view_type: ViewType::For {
+            each,
+            key,
+            item,
+            items: Ident::new(
+                &format!("__items{}", NEXT_ID.fetch_add(1, Ordering::SeqCst)),
+                Span::call_site(),
+            ),
+            child: Box::new(child.clone()),
+        },
+        constraint: child.constraint,
+        constraint_val: child.constraint_val,
+        create_dummy_parent: false,
+        layout_props: None,
+        interaction: None,
+        debug: None,
+    };
+    view.debug = DebugInfo::new("for".to_string(), &view);
+    view
+}
+
+/// Parses `<grid rows=.. columns=.. gap=..>`. Children default to the first
+/// cell with a span of one.
+fn parse_grid(cx_name: &TokenStream, element: &NodeElement, include_parent_id: bool) -> View {
+    let (mut values, attrs) =
+        parse_control_attrs(element, &["rows", "columns", "gap"], include_parent_id);
+    let gap = values.pop().flatten();
+    let Some(columns) = values.pop().flatten() else {
+        abort!(element.name(), "`<grid>` requires a `columns` attribute");
+    };
+    let Some(rows) = values.pop().flatten() else {
+        abort!(element.name(), "`<grid>` requires a `rows` attribute");
+    };
+    let children = element
+        .children
+        .iter()
+        .filter_map(|node| match node {
+            Node::Element(child) if child.name().to_string() == "for" => {
+                abort!(child.name(), "`<for>` can't be placed in a grid cell";
+                    help = "wrap it in a row or column")
+            }
+            Node::Element(child) => {
+                let (cell, child) = parse_grid_cell(child);
+                Some((cell, parse_element(cx_name, &child, include_parent_id)))
+            }
+            Node::Block(block) => {
+                abort!(block, "blocks can't be placed in a grid cell";
+                    help = "wrap it in a row or column")
+            }
+            _ => None,
+        })
+        .collect();
+    let interaction = Interaction::new(&attrs);
+
+    View {
+        view_type: ViewType::Grid {
+            rows,
+            columns,
+            gap,
+            children,
+        },
+        constraint: attrs.constraint,
+        constraint_val: attrs.expr,
+        create_dummy_parent: false,
+        layout_props: None,
+        interaction,
+        debug: None,
+    }
+}
+
+/// Parses `<scroll_view offset=.. height=..>`, whose children are laid out
+/// as a column.
+fn parse_scroll_view(
+    cx_name: &TokenStream,
+    element: &NodeElement,
+    include_parent_id: bool,
+) -> View {
+    let (mut values, attrs) =
+        parse_control_attrs(element, &["offset", "height"], include_parent_id);
+    let height = values.pop().flatten();
+    let Some(offset) = values.pop().flatten() else {
+        abort!(element.name(), "`<scroll_view>` requires an `offset` attribute";
+            help = "pass an `Rc<RefCell<ScrollViewState>>`");
+    };
+    let children = parse_elements(cx_name, &element.children, include_parent_id);
+    let interaction = Interaction::new(&attrs);
+
+    View {
+        view_type: ViewType::ScrollView {
+            offset,
+            state: Ident::new(
+                &format!("__scroll{}", NEXT_ID.fetch_add(1, Ordering::SeqCst)),
+                Span::call_site(),
+            ),
+            height,
+            children,
+        },
+        constraint: attrs.constraint,
+        constraint_val: attrs.expr,
+        create_dummy_parent: false,
+        layout_props: None,
+        interaction,
+        debug: None,
+    }
+}
+
+const GRID_CELL_ATTRS: &[&str] = &["row", "col", "row_span", "col_span"];
+
+/// Takes the placement attributes off a child of a `<grid>`, so the rest can
+/// be parsed as usual.
+fn parse_grid_cell(element: &NodeElement) -> (GridCell, NodeElement) {
+    let (values, element) = take_attributes(element, GRID_CELL_ATTRS);
+    let mut values = values.into_iter().map(|val| val.map(|val| quote!(#val)));
+    let mut next = |default| values.next().flatten().unwrap_or(default);
+    let cell = GridCell {
+        row: next(quote!(0)),
+        col: next(quote!(0)),
+        row_span: next(quote!(1)),
+        col_span: next(quote!(1)),
+    };
+    (cell, element)
+}
+
+/// Namespaced so they can't clash with the props of the placed element.
+const PLACEMENT_ATTRS: &[&str] = &[
+    "place:x",
+    "place:y",
+    "place:width",
+    "place:height",
+    "place:width_percent",
+    "place:height_percent",
+    "place:anchor",
+    "place:offset_x",
+    "place:offset_y",
+];
+
+const ANCHORS: &[&str] = &[
+    "top_left",
+    "top",
+    "top_right",
+    "left",
+    "center",
+    "right",
+    "bottom_left",
+    "bottom",
+    "bottom_right",
+];
+
+/// Parses the children of an `<overlay>`. Children without placement
+/// attributes are drawn over the whole overlay.
+fn parse_overlay_children(
+    cx_name: &TokenStream,
+    nodes: &[Node],
+    include_parent_id: bool,
+) -> Vec<(Option<TokenStream>, View)> {
+    nodes
+        .iter()
+        .flat_map(|node| match node {
+            Node::Element(element) if element.name().to_string() != "for" => {
+                let (placement, element) = parse_placement(element);
+                vec![(
+                    placement,
+                    parse_element(cx_name, &element, include_parent_id),
+                )]
+            }
+            node => parse_elements(cx_name, std::slice::from_ref(node), include_parent_id)
+                .into_iter()
+                .map(|view| (None, view))
+                .collect(),
+        })
+        .collect()
+}
+
+/// Takes the `place:` attributes off a child of an `<overlay>` and builds its
+/// `Placement`, if it has any.
+fn parse_placement(element: &NodeElement) -> (Option<TokenStream>, NodeElement) {
+    let (values, element) = take_attributes(element, PLACEMENT_ATTRS);
+    for node in element.attributes() {
+        if let NodeAttribute::Attribute(attribute) = node {
+            let key = attribute.key.to_string();
+            if key.starts_with("place:") {
+                abort!(
+                    attribute.key,
+                    "unknown placement attribute `{}`", key;
+                    help = diagnostic::suggest(&key, PLACEMENT_ATTRS)
+                );
+            }
+        }
+    }
+    let setters: Vec<_> = PLACEMENT_ATTRS
+        .iter()
+        .zip(values)
+        .filter_map(|(name, val)| {
+            let val = val?;
+            let name = &name["place:".len()..];
+            let setter = Ident::new(name, val.span());
+            let val = match val {
+                Expr::Path(path) if name == "anchor" => anchor_tokens(path),
+                val => quote!(#val),
+            };
+            Some(quote!(.#setter(#val)))
+        })
+        .collect();
+    if setters.is_empty() {
+        (None, element)
+    } else {
+        (Some(quote!(Placement::default() #(#setters)*)), element)
+    }
+}
+
+/// Allows `place:anchor=center` as a shorthand for
+/// `place:anchor=Anchor::Center`.
+fn anchor_tokens(path: ExprPath) -> TokenStream {
+    match path.path.get_ident().map(|ident| ident.to_string()) {
+        Some(anchor) if ANCHORS.contains(&anchor.as_str()) => {
+            let variant = Ident::new(&snake_case_to_pascal_case(&anchor), path.span());
+            quote!(Anchor::#variant)
+        }
+        _ => quote!(#path),
+    }
+}
+
+/// Removes the attributes in `names` from a copy of `element`, returning
+/// their values in order along with the copy.
+fn take_attributes(element: &NodeElement, names: &[&str]) -> (Vec<Option<Expr>>, NodeElement) {
+    let mut element = element.clone();
+    let mut values = vec![None; names.len()];
+    element.open_tag.attributes.retain(|node| {
+        let NodeAttribute::Attribute(attribute) = node else {
+            return true;
+        };
+        let key = attribute.key.to_string();
+        let Some(i) = names.iter().position(|name| *name == key) else {
+            return true;
+        };
+        values[i] = Some(required_value(attribute).clone());
+        false
+    });
+    (values, element)
+}
+
+/// Parses the attributes of a control flow element. Attributes in `names` are
+/// returned in order, the rest are treated as standard attributes such as
+/// constraints and event handlers.
+fn parse_control_attrs(
+    element: &NodeElement,
+    names: &[&str],
+    include_parent_id: bool,
+) -> (Vec<Option<Box<Expr>>>, NodeAttributes) {
+    let mut values = vec![None; names.len()];
+    let mut standard = vec![];
+    for node in element.attributes() {
+        if let NodeAttribute::Attribute(attribute) = node {
+            let key = attribute.key.to_string();
+            if let Some(i) = names.iter().position(|name| *name == key) {
+                values[i] = Some(Box::new(required_value(attribute).clone()));
+            } else if diagnostic::STANDARD_ATTRS.contains(&key.as_str()) {
+                standard.push(node.clone());
+            } else {
+                let known: Vec<_> = names
+                    .iter()
+                    .chain(diagnostic::STANDARD_ATTRS)
+                    .copied()
+                    .collect();
+                abort!(
+                    attribute.key,
+                    "unknown `<{}>` attribute `{}`", element.name(), key;
+                    help = diagnostic::suggest(&key, &known)
+                );
+            }
+        }
+    }
+    let attrs = NodeAttributes::from_layout_nodes(&standard, include_parent_id);
+    (values, attrs)
+}
+
+/// Parses the single child of a `<show>` or `<case>`.
+fn parse_branch(cx_name: &TokenStream, element: &NodeElement, include_parent_id: bool) -> View {
+    let mut children = parse_elements(cx_name, &element.children, include_parent_id);
+    if children.len() != 1 {
+        abort!(
+            element.name(),
+            "`<{}>` should contain a single child",
+            element.name()
+        );
+    }
+    let child = children.remove(0);
+    if let ViewType::For { .. } = child.view_type {
+        abort!(
+            element.name(),
+            "`<for>` must be wrapped in a row, column or overlay here"
+        );
+    }
+    child
+}
+
+fn parse_show(cx_name: &TokenStream, element: &NodeElement, include_parent_id: bool) -> View {
+    let (mut values, attrs) =
+        parse_control_attrs(element, &["when", "fallback"], include_parent_id);
+    let fallback = values.pop().flatten();
+    let Some(when) = values.pop().flatten() else {
+        abort!(element.name(), "`<show>` requires a `when` attribute");
+    };
+    let child = parse_branch(cx_name, element, include_parent_id);
+    let interaction = Interaction::new(&attrs);
+
+    View {
+        view_type: ViewType::Show {
+            when,
+            child: Box::new(child),
+            fallback,
+        },
+        constraint: attrs.constraint,
+        constraint_val: attrs.expr,
+        create_dummy_parent: false,
+        layout_props: None,
+        interaction,
+        debug: None,
+    }
+}
+
+fn parse_switch(cx_name: &TokenStream, element: &NodeElement, include_parent_id: bool) -> View {
+    let (mut values, attrs) = parse_control_attrs(element, &["value"], include_parent_id);
+    let Some(value) = values.pop().flatten() else {
+        abort!(element.name(), "`<switch>` requires a `value` attribute");
+    };
+    let cases: Vec<_> = element
+        .children
+        .iter()
+        .filter_map(|node| match node {
+            Node::Element(case) if case.name().to_string() == "case" => {
+                let (mut values, _) = parse_control_attrs(case, &["pattern"], include_parent_id);
+                let Some(pattern) = values.pop().flatten() else {
+                    abort!(case.name(), "`<case>` requires a `pattern` attribute");
+                };
+                Some((pattern, parse_branch(cx_name, case, include_parent_id)))
+            }
+            Node::Element(other) => {
+                abort!(
+                    other.name(),
+                    "`<switch>` can only contain `<case>` elements";
+                    help = "wrap it in `<case pattern=..>`"
+                )
+            }
+            _ => None,
+        })
+        .collect();
+    if cases.is_empty() || cases.len() > 8 {
+        abort!(
+            element.name(),
+            "`<switch>` should contain between 1 and 8 cases"
+        );
+    }
+    let interaction = Interaction::new(&attrs);
+
+    View {
+        view_type: ViewType::Switch { value, cases },
+        constraint: attrs.constraint,
+        constraint_val: attrs.expr,
+        create_dummy_parent: false,
+        layout_props: None,
+        interaction,
+        debug: None,
+    }
+}
+
+fn parse_widget(
+    cx_name: &TokenStream,
+    element: &NodeElement,
+    children: TokenStream,
+    setters: &[TokenStream],
+    include_parent_id: bool,
+) -> View {
+    let attrs = NodeAttributes::from_custom(
+        Some(cx_name),
+        element,
+        children,
+        setters,
+        "Props",
+        include_parent_id,
+    );
+    let interaction = Interaction::new(&attrs);
+    View {
+        view_type: ViewType::Element {
+            name: diagnostic::ident(&element.name().to_string(), element.name().span()),
+            fn_name: Ident::new(
+                &format!("__fn{}", NEXT_ID.fetch_add(1, Ordering::SeqCst)),
+                element.name().span(),
+            ),
+            props: attrs.props,
+            state: attrs.state,
+        },
+        constraint: attrs.constraint,
+        constraint_val: attrs.expr,
+        create_dummy_parent: false,
+        layout_props: None,
+        interaction,
+        debug: None,
+    }
+}
+
+/// Splits the children of a `<chart>` into the datasets passed to `Chart::new`
+/// and the `x_axis`/`y_axis` setters applied afterwards.
+fn parse_chart_children(
+    nodes: &[Node],
+    include_parent_id: bool,
+) -> (TokenStream, Vec<TokenStream>) {
+    let mut datasets = vec![];
+    let mut axes = vec![];
+    for node in nodes {
+        match node {
+            Node::Element(element) => {
+                let name = element.name().to_string();
+                match name.as_str() {
+                    "dataset" => {
+                        let attrs = NodeAttributes::from_custom(
+                            None,
+                            element,
+                            TokenStream::default(),
+                            &[],
+                            "",
+                            include_parent_id,
+                        );
+                        if let Some(props) = attrs.props {
+                            datasets.push(props);
+                        }
+                    }
+                    "x_axis" | "y_axis" => {
+                        let attrs = NodeAttributes::from_nodes(
+                            None,
+                            Some(&Ident::new("Axis", element.name().span())),
+                            element.attributes(),
+                            None,
+                            &[],
+                            "",
+                            include_parent_id,
+                        );
+                        if let Some(props) = attrs.props {
+                            let setter = Ident::new(&name, element.name().span());
+                            axes.push(quote!(.#setter(#props)));
+                        }
+                    }
+                    name => {
+                        abort!(
+                            element.name(),
+                            "`<{}>` can't be placed in a `<chart>`", name;
+                            help = diagnostic::suggest(name, &["dataset", "x_axis", "y_axis"])
+                        );
+                    }
+                }
+            }
+            Node::Block(block) => {
+                if let Some(block) = block.try_block() {
+                    let content = get_block_contents(block);
+                    datasets.push(content);
+                }
+            }
+            _ => {}
+        }
+    }
+    (quote!(vec![#(#datasets),*]), axes)
+}
+
+const CANVAS_SHAPES: &[&str] = &["line", "rectangle", "points", "circle", "label"];
+
+/// Compiles the shape children of a `<canvas>` into its `paint` callback.
+/// Blocks are pasted into the callback as-is and can use `ctx` directly.
+fn parse_canvas_children(nodes: &[Node], include_parent_id: bool) -> TokenStream {
+    let mut stmts = vec![];
+    for node in nodes {
+        match node {
+            Node::Element(element) => {
+                let name = element.name().to_string();
+                match name.as_str() {
+                    "line" => stmts.push(canvas_shape(
+                        element,
+                        "Line",
+                        &[
+                            ("x1", quote!(0.0)),
+                            ("y1", quote!(0.0)),
+                            ("x2", quote!(0.0)),
+                            ("y2", quote!(0.0)),
+                            ("color", quote!(Color::Reset)),
+                        ],
+                    )),
+                    "rectangle" => stmts.push(canvas_shape(
+                        element,
+                        "Rectangle",
+                        &[
+                            ("x", quote!(0.0)),
+                            ("y", quote!(0.0)),
+                            ("width", quote!(0.0)),
+                            ("height", quote!(0.0)),
+                            ("color", quote!(Color::Reset)),
+                        ],
+                    )),
+                    "points" => stmts.push(canvas_shape(
+                        element,
+                        "Points",
+                        &[("coords", quote!(&[])), ("color", quote!(Color::Reset))],
+                    )),
+                    "circle" => stmts.push(canvas_shape(
+                        element,
+                        "Circle",
+                        &[
+                            ("x", quote!(0.0)),
+                            ("y", quote!(0.0)),
+                            ("radius", quote!(0.0)),
+                            ("color", quote!(Color::Reset)),
+                        ],
+                    )),
+                    "label" => {
+                        let fields =
+                            canvas_fields(element, &[("x", quote!(0.0)), ("y", quote!(0.0))]);
+                        let (x, y) = (&fields[0].1, &fields[1].1);
+                        let text =
+                            parse_named_element_children(&element.children, include_parent_id);
+                        if text.is_empty() {
+                            abort!(element.name(), "`<label>` requires text";
+                                help = "add the text as a child, e.g. `<label x=0.0 y=0.0>\"text\"</label>`");
+                        }
+                        stmts.push(quote! { ctx.print(#x, #y, #text); });
+                    }
+                    name => {
+                        abort!(
+                            element.name(),
+                            "`<{}>` can't be placed in a `<canvas>`", name;
+                            help = diagnostic::suggest(name, CANVAS_SHAPES)
+                        );
+                    }
+                }
+            }
+            Node::Block(block) => {
+                if let Some(block) = block.try_block() {
+                    let content = get_block_contents(block);
+                    stmts.push(quote! { #content; });
+                }
+            }
+            _ => {}
+        }
+    }
+    quote! {
+        .paint(move |ctx: &mut canvas::Context| {
+            #(#stmts)*
+        })
+    }
+}
+
+fn canvas_shape(
+    element: &NodeElement,
+    shape: &str,
+    defaults: &[(&str, TokenStream)],
+) -> TokenStream {
+    let span = element.name().span();
+    let shape = Ident::new(shape, span);
+    let fields = canvas_fields(element, defaults)
+        .into_iter()
+        .map(|(name, val)| {
+            let name = Ident::new(&name, span);
+            quote!(#name: #val)
+        });
+    quote! {
+        ctx.draw(&canvas::#shape {
+            #(#fields),*
+        });
+    }
+}
+
+/// Pairs each shape field with its attribute value, or its default if the
+/// attribute is missing.
+fn canvas_fields(
+    element: &NodeElement,
+    defaults: &[(&str, TokenStream)],
+) -> Vec<(String, TokenStream)> {
+    let mut fields: Vec<_> = defaults
+        .iter()
+        .map(|(name, val)| (name.to_string(), val.clone()))
+        .collect();
+    for node in element.attributes() {
+        if let NodeAttribute::Attribute(attribute) = node {
+            let key = attribute.key.to_string();
+            let Some(field) = fields.iter_mut().find(|(name, _)| *name == key) else {
+                let expected: Vec<_> = defaults.iter().map(|(name, _)| *name).collect();
+                abort!(
+                    attribute.key,
+                    "unknown `<{}>` attribute `{}`", element.name(), key;
+                    help = diagnostic::suggest(&key, &expected)
+                );
+            };
+            field.1 = required_value(attribute).to_token_stream();
+        }
     }
+    fields
 }
 
 fn capitalize(s: &str) -> String {
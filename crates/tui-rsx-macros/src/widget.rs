@@ -0,0 +1,226 @@
+use crate::get_import;
+use convert_case::{Case::Snake, Casing};
+use proc_macro2::{Ident, TokenStream};
+use proc_macro_error::abort;
+use quote::{format_ident, quote};
+use syn::{
+    parse::Parse, punctuated::Punctuated, Expr, GenericParam, ItemType, Meta, Token, WherePredicate,
+};
+
+#[derive(Default)]
+pub(crate) struct WidgetOpts {
+    name: Option<Ident>,
+    skip_make_builder: bool,
+    builder: Option<Expr>,
+}
+
+impl Parse for WidgetOpts {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut opts = Self::default();
+        for meta in Punctuated::<Meta, Token![,]>::parse_terminated(input)? {
+            match &meta {
+                Meta::Path(path) if path.is_ident("skip_make_builder") => {
+                    opts.skip_make_builder = true;
+                }
+                Meta::NameValue(name_value) if name_value.path.is_ident("name") => {
+                    let Expr::Path(path) = &name_value.value else {
+                        abort!(name_value.value, "expected a function name");
+                    };
+                    let Some(name) = path.path.get_ident() else {
+                        abort!(path, "expected a function name");
+                    };
+                    opts.name = Some(name.clone());
+                }
+                Meta::NameValue(name_value) if name_value.path.is_ident("builder") => {
+                    opts.builder = Some(name_value.value.clone());
+                }
+                meta => {
+                    abort!(
+                        meta,
+                        "unknown widget option";
+                        help = "expected `name = ...`, `builder = ...` or `skip_make_builder`"
+                    );
+                }
+            }
+        }
+        Ok(opts)
+    }
+}
+
+/// The pieces shared by stateless and stateful widget registrations.
+struct Registration {
+    fn_name: Ident,
+    props: TokenStream,
+    fn_generics: Vec<TokenStream>,
+    where_predicates: Vec<WherePredicate>,
+    make_builder: TokenStream,
+}
+
+impl Registration {
+    fn new(alias: &ItemType, opts: &WidgetOpts) -> Self {
+        let crate_import = get_import();
+        let alias_name = &alias.ident;
+        let fn_name = opts.name.clone().unwrap_or_else(|| {
+            let name = alias_name.to_string();
+            let Some(base) = name.strip_suffix("Props") else {
+                abort!(
+                    alias_name,
+                    "widget type aliases must end with `Props`";
+                    help = "try `{}Props` or set the function name with `name = ...`", name
+                );
+            };
+            Ident::new(&base.to_case(Snake), alias_name.span())
+        });
+
+        // Props are stored in a 'static view so every lifetime is filled in
+        // with 'static and type parameters are forwarded to the function.
+        let args = alias.generics.params.iter().map(|param| match param {
+            GenericParam::Lifetime(_) => quote!('static),
+            GenericParam::Type(ty) => {
+                let ident = &ty.ident;
+                quote!(#ident)
+            }
+            GenericParam::Const(c) => {
+                let ident = &c.ident;
+                quote!(#ident)
+            }
+        });
+        let props = quote!(#alias_name<#(#args),*>);
+
+        let fn_generics = alias
+            .generics
+            .type_params()
+            .map(|param| {
+                let ident = &param.ident;
+                let bounds = &param.bounds;
+                if bounds.is_empty() {
+                    quote!(#ident: 'static)
+                } else {
+                    quote!(#ident: #bounds + 'static)
+                }
+            })
+            .collect();
+        let where_predicates = alias
+            .generics
+            .where_clause
+            .as_ref()
+            .map(|w| w.predicates.iter().cloned().collect())
+            .unwrap_or_default();
+
+        let make_builder = if opts.skip_make_builder {
+            quote!()
+        } else {
+            let (impl_generics, ty_generics, where_clause) = alias.generics.split_for_impl();
+            quote! {
+                impl #impl_generics #crate_import::MakeBuilder for #alias_name #ty_generics #where_clause {}
+            }
+        };
+
+        Self {
+            fn_name,
+            props,
+            fn_generics,
+            where_predicates,
+            make_builder,
+        }
+    }
+}
+
+pub(crate) fn widget(opts: WidgetOpts, alias: ItemType) -> TokenStream {
+    let crate_import = get_import();
+    let Registration {
+        fn_name,
+        props,
+        fn_generics,
+        where_predicates,
+        make_builder,
+    } = Registration::new(&alias, &opts);
+    let vis = &alias.vis;
+    let builder_facade = builder_facade(&alias, &opts);
+
+    quote! {
+        #alias
+
+        #make_builder
+
+        #builder_facade
+
+        #vis fn #fn_name<T, B: #crate_import::ratatui::backend::Backend, #(#fn_generics),*>(
+            _cx: T,
+            props: #props,
+        ) -> impl #crate_import::View<B>
+        where
+            #(#where_predicates,)*
+        {
+            move |frame: &mut #crate_import::ratatui::Frame<B>,
+                  rect: #crate_import::ratatui::layout::Rect| {
+                frame.render_widget(&props, rect)
+            }
+        }
+    }
+}
+
+/// Widgets without a `Default` impl can't use the blanket `BuilderFacade`, so
+/// an extension trait named after the alias provides `builder()` instead, the
+/// same way `WrapExt` does for `Wrap`.
+fn builder_facade(alias: &ItemType, opts: &WidgetOpts) -> TokenStream {
+    let Some(builder) = &opts.builder else {
+        return quote!();
+    };
+    let alias_name = &alias.ident;
+    let name = alias_name.to_string();
+    let ext_name = format_ident!(
+        "{}Ext",
+        name.strip_suffix("Props").unwrap_or(&name),
+        span = alias_name.span()
+    );
+    let vis = &alias.vis;
+    let (impl_generics, ty_generics, where_clause) = alias.generics.split_for_impl();
+
+    quote! {
+        #vis trait #ext_name {
+            fn builder() -> Self;
+        }
+
+        impl #impl_generics #ext_name for #alias_name #ty_generics #where_clause {
+            fn builder() -> Self {
+                #builder
+            }
+        }
+    }
+}
+
+pub(crate) fn stateful_widget(opts: WidgetOpts, alias: ItemType) -> TokenStream {
+    let crate_import = get_import();
+    let Registration {
+        fn_name,
+        props,
+        fn_generics,
+        where_predicates,
+        make_builder,
+    } = Registration::new(&alias, &opts);
+    let vis = &alias.vis;
+    let builder_facade = builder_facade(&alias, &opts);
+
+    quote! {
+        #alias
+
+        #make_builder
+
+        #builder_facade
+
+        #vis fn #fn_name<T, B: #crate_import::ratatui::backend::Backend, #(#fn_generics),*>(
+            _cx: T,
+            props: #props,
+            mut state: impl #crate_import::StatefulRender<B, #props> + 'static,
+        ) -> impl #crate_import::View<B>
+        where
+            #(#where_predicates,)*
+        {
+            move |frame: &mut #crate_import::ratatui::Frame<B>,
+                  rect: #crate_import::ratatui::layout::Rect| {
+                state.render_with_state(&props, frame, rect);
+            }
+        }
+    }
+}
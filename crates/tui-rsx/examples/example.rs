@@ -1,16 +1,18 @@
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
-use leptos_reactive::{create_runtime, create_scope, Scope};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    terminal::{disable_raw_mode, enable_raw_mode},
+};
+use leptos_reactive::{create_runtime, create_scope, create_signal, ReadSignal, Scope};
 use ratatui::{backend::Backend, backend::CrosstermBackend, Terminal, TerminalOptions, Viewport};
-use std::io;
-use tui_rsx::prelude::*;
-use typed_builder::TypedBuilder;
+use std::{io, time::Duration};
+use tui_rsx::{prelude::*, reactive::draw_if_dirty};
 
 pub fn main() {
-    create_scope(create_runtime(), run).dispose();
+    create_scope(create_runtime(), |cx| run(cx).unwrap()).dispose();
 }
 
-fn run(cx: Scope) {
-    enable_raw_mode().unwrap();
+fn run(cx: Scope) -> io::Result<()> {
+    enable_raw_mode()?;
 
     let stdout = io::stdout();
     let backend = CrosstermBackend::new(stdout);
@@ -19,49 +21,61 @@ fn run(cx: Scope) {
         TerminalOptions {
             viewport: Viewport::Inline(8),
         },
-    )
-    .unwrap();
-    let view = view! { cx,
-        <Column>
-            <block default length=4/>
-            <counter count=0/>
-            <Viewer text="blah".to_string()/>
+    )?;
 
-        </Column>
+    let (count, set_count) = create_signal(cx, 0);
+    let mut view = mount! { cx,
+        <column>
+            <block length=4 title="press + to count, q to quit"/>
+            <Counter count=count/>
+            <Viewer text="blah"/>
+        </column>
     };
 
-    terminal
-        .draw(|f| {
-            view(f, f.size());
-        })
-        .unwrap();
+    loop {
+        // Only redraws after `count` changes
+        draw_if_dirty(cx, &mut terminal, |f| view.view(f, f.size()))?;
 
-    disable_raw_mode().unwrap();
-    println!();
-}
+        if event::poll(Duration::from_millis(250))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    match key.code {
+                        KeyCode::Char('+') => set_count.update(|c| *c += 1),
+                        KeyCode::Char('q') => break,
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
 
-#[derive(TypedBuilder)]
-pub struct CounterProps {
-    count: usize,
+    disable_raw_mode()?;
+    println!();
+    Ok(())
 }
 
-fn counter<B: Backend>(cx: Scope, props: CounterProps) -> impl Fn(&mut Frame<B>, Rect) {
-    let CounterProps { count } = props;
-    view! { cx,
-        <block title=format!("count {count}")/>
+#[component]
+fn Counter<B: Backend + 'static>(cx: Scope, count: ReadSignal<usize>) -> impl View<B> {
+    move || {
+        let count = count.get();
+        view! { cx,
+            <block title=format!("count {count}")/>
+        }
     }
 }
 
 #[component]
-fn viewer<B: Backend>(
+fn Viewer<B: Backend + 'static>(
     cx: Scope,
-    text: String,
-    #[prop(default = 20)] blah: usize,
-) -> impl Fn(&mut Frame<B>, Rect) {
-    view! { cx,
-        <list>
-            <listItem>{text}</listItem>
-            <listItem>"test2"</listItem>
-        </list>
+    #[prop(into)] text: String,
+    #[prop(default = 20)] _blah: usize,
+) -> impl View<B> {
+    move || {
+        view! { cx,
+            <list>
+                <listItem>{text.clone()}</listItem>
+                <listItem>"test2"</listItem>
+            </list>
+        }
     }
 }
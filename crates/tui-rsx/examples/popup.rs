@@ -82,19 +82,11 @@ fn ui<B: Backend + 'static>(f: &mut Frame<B>, app: &App) {
                 </paragraph>
                 <block percentage=80 title="Content" borders=Borders::ALL on_blue/>
             </column>
-            {
-                if app.show_popup {
-                    view! {
-                        <Popup percent_x=60 percent_y=20>
-                            {move || view!(<block title="Popup" borders=Borders::ALL/>)}
-                        </Popup>
-                    }.into_boxed_view()
-                } else {
-                    view! {
-                        <column/>
-                    }.into_boxed_view()
-                }
-            }
+            <show when=app.show_popup>
+                <Popup percent_x=60 percent_y=20>
+                    {move || view!(<block title="Popup" borders=Borders::ALL/>)}
+                </Popup>
+            </show>
         </overlay>
     };
     view.view(f, f.size());
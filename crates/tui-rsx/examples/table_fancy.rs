@@ -26,7 +26,8 @@ pub fn main() {
             header=prop!(
                 <row bottom_margin=1 style=prop!(<style fg=Color::Yellow/>)>
                     "Col1" "Col2" "Col3"
-                </row>)
+                </row>
+            )
             block=prop!(<block title="Table"/>)
             widths=&[Constraint::Length(5), Constraint::Length(5), Constraint::Length(10)]
             column_spacing=1
@@ -0,0 +1,36 @@
+//! The time views should use for animations, timers and polling. It follows
+//! the system clock, except under a [`Harness`](crate::testing::Harness),
+//! which stops it and only moves it forward on
+//! [`tick`](crate::testing::Harness::tick).
+
+use std::{cell::Cell, time::Instant};
+
+#[cfg(feature = "testing")]
+use std::time::Duration;
+
+thread_local! {
+    static FROZEN: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+pub fn now() -> Instant {
+    FROZEN
+        .with(|frozen| frozen.get())
+        .unwrap_or_else(Instant::now)
+}
+
+/// Stops the clock at the current time, returning what it was set to before
+/// so that it can be restored.
+#[cfg(feature = "testing")]
+pub(crate) fn freeze() -> Option<Instant> {
+    FROZEN.with(|frozen| frozen.replace(Some(now())))
+}
+
+#[cfg(feature = "testing")]
+pub(crate) fn restore(previous: Option<Instant>) {
+    FROZEN.with(|frozen| frozen.set(previous));
+}
+
+#[cfg(feature = "testing")]
+pub(crate) fn advance(duration: Duration) {
+    FROZEN.with(|frozen| frozen.set(Some(now() + duration)));
+}
@@ -15,20 +15,16 @@ where
     move || {
         let mut children = children.clone();
         view! {
-            <column>
-                <row percentage=(100 - percent_y)/2 />
-                <row percentage=percent_y>
-                    <column percentage=(100 - percent_x)/2 />
-                    <column percentage=percent_x>
-                        <overlay>
-                            <clear/>
-                            {children}
-                        </overlay>
-                    </column>
-                    <column percentage=(100 - percent_x)/2 />
-                </row>
-                <row percentage=(100 - percent_y)/2 />
-            </column>
+            <overlay>
+                <overlay
+                    place:anchor=center
+                    place:width_percent=percent_x
+                    place:height_percent=percent_y
+                >
+                    <clear/>
+                    {children}
+                </overlay>
+            </overlay>
         }
     }
 }
@@ -0,0 +1,63 @@
+use ratatui::layout::{Constraint, Rect};
+
+/// Splits a container's rect between its children. Any type implementing it
+/// that is also `Default` can be used as a tag with `<layout:name>`, where
+/// `name` is the type's name in snake case and attributes call setters on it.
+///
+/// ```ignore
+/// #[derive(Default)]
+/// struct Sidebar {
+///     collapsed: bool,
+/// }
+///
+/// impl Sidebar {
+///     fn collapsed(mut self, collapsed: bool) -> Self {
+///         self.collapsed = collapsed;
+///         self
+///     }
+/// }
+///
+/// impl LayoutContainer for Sidebar {
+///     fn split(&self, rect: Rect, children: &[ChildMeta]) -> Vec<Rect> {
+///         // ...
+///     }
+/// }
+///
+/// view! {
+///     <layout:sidebar collapsed=true>
+///         <list length=20>..</list>
+///         <paragraph>..</paragraph>
+///     </layout:sidebar>
+/// }
+/// ```
+pub trait LayoutContainer {
+    /// Returns one rect per child, in order. Children without a rect are
+    /// rendered into an empty one.
+    fn split(&self, rect: Rect, children: &[ChildMeta]) -> Vec<Rect>;
+}
+
+/// What a container knows about each child: the constraint set with its
+/// `min`, `max`, `percentage` or `length` attribute.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ChildMeta {
+    pub constraint: Constraint,
+}
+
+impl ChildMeta {
+    pub fn new(constraint: Constraint) -> Self {
+        Self { constraint }
+    }
+}
+
+/// Splits `rect` with `container`, with one rect for every child.
+pub fn split_container(
+    container: &impl LayoutContainer,
+    rect: Rect,
+    constraints: impl IntoIterator<Item = Constraint>,
+) -> Vec<Rect> {
+    let children: Vec<_> = constraints.into_iter().map(ChildMeta::new).collect();
+    let mut chunks = container.split(rect, &children);
+    chunks.resize(children.len(), Rect::default());
+    chunks
+}
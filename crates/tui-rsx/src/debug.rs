@@ -0,0 +1,226 @@
+//! Records every element rendered by `view!` and `mount!`, along with its
+//! tag, `key`, constraint and final rect. Enabled with the `debug` feature,
+//! and toggled at runtime with [`set_enabled`].
+//!
+//! ```ignore
+//! debug::set_outlines(true);
+//! terminal.draw(|f| view.view(f, f.size()))?;
+//! eprintln!("{}", debug::dump());
+//! ```
+
+use crate::{scroll, View};
+use ratatui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders},
+    Frame,
+};
+use std::{cell::RefCell, fmt::Write};
+
+thread_local! {
+    static RECORDER: RefCell<Recorder> = const {
+        RefCell::new(Recorder {
+            enabled: true,
+            outlines: false,
+            depth: 0,
+            elements: Vec::new(),
+        })
+    };
+}
+
+struct Recorder {
+    enabled: bool,
+    outlines: bool,
+    depth: usize,
+    elements: Vec<ElementRecord>,
+}
+
+/// What the macro knows about an element.
+#[derive(Clone, Debug)]
+pub struct ElementInfo {
+    tag: &'static str,
+    constraint: &'static str,
+    key: Option<String>,
+}
+
+impl ElementInfo {
+    pub fn new(tag: &'static str, constraint: &'static str, key: Option<String>) -> Self {
+        Self {
+            tag,
+            constraint,
+            key,
+        }
+    }
+
+    /// The same element under another key, e.g. one item of a `<for>`.
+    pub fn keyed(&self, key: String) -> Self {
+        Self {
+            key: Some(key),
+            ..self.clone()
+        }
+    }
+}
+
+/// An element as it was rendered in the last frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ElementRecord {
+    pub tag: &'static str,
+    pub constraint: &'static str,
+    pub key: Option<String>,
+    pub rect: Rect,
+    /// How many recorded elements it's nested in.
+    pub depth: usize,
+}
+
+impl ElementRecord {
+    fn label(&self) -> String {
+        match &self.key {
+            Some(key) => format!("{}#{}", self.tag, key),
+            None => self.tag.to_string(),
+        }
+    }
+}
+
+pub fn set_enabled(enabled: bool) {
+    RECORDER.with(|recorder| recorder.borrow_mut().enabled = enabled);
+}
+
+pub fn is_enabled() -> bool {
+    RECORDER.with(|recorder| recorder.borrow().enabled)
+}
+
+/// Draws an outline and label over every recorded element at the end of
+/// each frame.
+pub fn set_outlines(outlines: bool) {
+    RECORDER.with(|recorder| recorder.borrow_mut().outlines = outlines);
+}
+
+/// The elements rendered in the last frame, in tree order.
+pub fn elements() -> Vec<ElementRecord> {
+    RECORDER.with(|recorder| recorder.borrow().elements.clone())
+}
+
+/// The elements rendered in the last frame as an indented tree, one per line.
+/// Elements that got no space are marked as empty.
+pub fn dump() -> String {
+    let mut out = String::new();
+    for element in elements() {
+        let Rect {
+            x,
+            y,
+            width,
+            height,
+        } = element.rect;
+        let _ = write!(
+            out,
+            "{:indent$}{} {} {x},{y} {width}x{height}",
+            "",
+            element.label(),
+            element.constraint,
+            indent = element.depth * 2
+        );
+        if element.rect.area() == 0 {
+            out.push_str(" (empty)");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Draws an outline labelled with its tag and key around every element
+/// recorded in the last frame.
+pub fn draw_outlines<B: Backend>(frame: &mut Frame<B>) {
+    let style = Style::default().fg(Color::Yellow);
+    for element in elements() {
+        if element.rect.area() == 0 {
+            continue;
+        }
+        let outline = Block::default()
+            .borders(Borders::ALL)
+            .border_style(style)
+            .title(element.label());
+        frame.render_widget(&outline, element.rect);
+    }
+}
+
+/// Starts recording a new frame. Called by the outermost `RootView`.
+pub(crate) fn begin_frame() {
+    RECORDER.with(|recorder| {
+        let mut recorder = recorder.borrow_mut();
+        recorder.elements.clear();
+        recorder.depth = 0;
+    });
+}
+
+/// Draws the outlines if they're turned on. Called by the outermost
+/// `RootView`.
+pub(crate) fn end_frame<B: Backend>(frame: &mut Frame<B>) {
+    let outlines = RECORDER.with(|recorder| {
+        let recorder = recorder.borrow();
+        recorder.enabled && recorder.outlines
+    });
+    if outlines {
+        draw_outlines(frame);
+    }
+}
+
+/// How many elements have been recorded so far this frame.
+pub(crate) fn recorded() -> usize {
+    RECORDER.with(|recorder| recorder.borrow().elements.len())
+}
+
+/// Moves the elements recorded since `start` to where a `<scroll_view>`
+/// scrolled by `offset` drew them, clipped to `viewport`.
+pub(crate) fn scroll(start: usize, offset: u16, viewport: Rect) {
+    RECORDER.with(|recorder| {
+        for element in &mut recorder.borrow_mut().elements[start..] {
+            element.rect = scroll::scrolled(element.rect, offset, viewport);
+        }
+    });
+}
+
+/// Records an element each time it's rendered.
+pub struct Inspect<V> {
+    view: V,
+    info: ElementInfo,
+}
+
+impl<V> Inspect<V> {
+    pub fn new(view: V, info: ElementInfo) -> Self {
+        Self { view, info }
+    }
+}
+
+impl<B, V> View<B> for Inspect<V>
+where
+    B: Backend,
+    V: View<B> + 'static,
+{
+    fn view(&mut self, frame: &mut Frame<B>, rect: Rect) {
+        let recording = RECORDER.with(|recorder| {
+            let mut recorder = recorder.borrow_mut();
+            if !recorder.enabled {
+                return false;
+            }
+            let depth = recorder.depth;
+            recorder.elements.push(ElementRecord {
+                tag: self.info.tag,
+                constraint: self.info.constraint,
+                key: self.info.key.clone(),
+                rect,
+                depth,
+            });
+            recorder.depth += 1;
+            true
+        });
+        self.view.view(frame, rect);
+        if recording {
+            RECORDER.with(|recorder| recorder.borrow_mut().depth -= 1);
+        }
+    }
+
+    fn into_boxed_view(self) -> Box<dyn View<B>> {
+        Box::new(self)
+    }
+}
@@ -0,0 +1,691 @@
+//! Builds views from RSX text at runtime, so layouts can be changed without
+//! recompiling. The text uses the same grammar as `view!`, limited to what can
+//! be evaluated without a compiler: `<row>`, `<column>` and `<overlay>`, the
+//! `length`, `min`, `max` and `percentage` constraints, the built-in
+//! `<block>`, `<paragraph>`, `<gauge>` and `<clear>` widgets, and components
+//! registered by name. Attribute values must be literals or paths such as
+//! `Borders::ALL`.
+//!
+//! ```ignore
+//! let mut components = ComponentRegistry::new();
+//! components.register("Clock", |_attrs| Ok(clock().into_boxed_view()));
+//! let mut view = components.parse(r#"
+//!     <column>
+//!         <block length=3 title="Status" borders=Borders::ALL/>
+//!         <Clock/>
+//!     </column>
+//! "#)?;
+//! ```
+//!
+//! Component constructors take `&mut Attrs` and return a `Result`, rather than
+//! taking an `&AttrMap` and returning the view. Taking attributes off
+//! [`Attrs`] lets the ones a component doesn't use be reported as unknown, and
+//! a value of the wrong type is reported at its position. `<row>`, `<column>`
+//! and `<overlay>` aren't constructors like the built-in widgets, since a
+//! constructor doesn't receive children, so the parser lays them out itself.
+//!
+//! A [`FileView`] loads a file and reloads it whenever it changes.
+//!
+//! Event handlers inside registered components only fire when the view is
+//! rendered inside a [`RootView`](crate::RootView), e.g. one created with
+//! `RootView::new(view)`.
+
+use crate::{clock, View};
+use proc_macro2::{Span, TokenStream};
+use ratatui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    text::Text,
+    widgets::{Block, BorderType, Borders, Clear, Gauge, Paragraph},
+    Frame,
+};
+use rstml::node::{KeyedAttribute, Node, NodeAttribute, NodeElement};
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, Instant, SystemTime},
+};
+use syn::{spanned::Spanned, BinOp, Expr, Lit, UnOp};
+
+/// The attributes of an element, other than its constraint.
+pub type AttrMap = HashMap<String, AttrValue>;
+
+/// The value of an attribute. Attributes without a value are `Bool(true)`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttrValue {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    /// A path such as `Borders::ALL`, or paths joined with `|`.
+    Path(String),
+}
+
+impl AttrValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AttrValue::Str(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            AttrValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            AttrValue::Float(value) => Some(*value),
+            AttrValue::Int(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            AttrValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_path(&self) -> Option<&str> {
+        match self {
+            AttrValue::Path(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// An error in RSX text, at a 1-based line and column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl ParseError {
+    /// An error in the source as a whole, reported at its start.
+    fn whole(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn new(span: Span, message: impl Into<String>) -> Self {
+        let start = span.start();
+        Self {
+            message: message.into(),
+            line: start.line,
+            column: start.column + 1,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+type Component<B> = Box<dyn Fn(&mut Attrs) -> Result<Box<dyn View<B>>, ParseError>>;
+
+/// Components that RSX text can refer to by name, alongside the built-in
+/// elements.
+pub struct ComponentRegistry<B: Backend> {
+    components: HashMap<String, Component<B>>,
+}
+
+impl<B: Backend + 'static> Default for ComponentRegistry<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: Backend + 'static> ComponentRegistry<B> {
+    pub fn new() -> Self {
+        Self {
+            components: HashMap::new(),
+        }
+    }
+
+    /// Makes `<name .. />` build a view with `component`. Registered names
+    /// take precedence over the built-in widgets. The component takes the
+    /// attributes it understands from [`Attrs`], and any left over are
+    /// reported as unknown.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        component: impl Fn(&mut Attrs) -> Result<Box<dyn View<B>>, ParseError> + 'static,
+    ) -> &mut Self {
+        self.components.insert(name.into(), Box::new(component));
+        self
+    }
+
+    /// Parses `source`, which should contain a single root element.
+    pub fn parse(&self, source: &str) -> Result<Box<dyn View<B>>, ParseError> {
+        let tokens = TokenStream::from_str(source)
+            .map_err(|e| ParseError::new(e.span(), "invalid token"))?;
+        if tokens.is_empty() {
+            return Err(ParseError::whole("RSX should contain a single root node"));
+        }
+        let nodes = rstml::parse2(tokens).map_err(|e| ParseError::new(e.span(), e.to_string()))?;
+        let mut roots = nodes
+            .iter()
+            .filter(|node| !matches!(node, Node::Comment(_)));
+        match (roots.next(), roots.next()) {
+            (Some(Node::Element(element)), None) => Ok(self.parse_element(element)?.1),
+            (Some(node), None) => Err(ParseError::new(
+                node.span(),
+                "RSX root node should be a named element",
+            )),
+            (_, Some(node)) => Err(ParseError::new(
+                node.span(),
+                "RSX should contain a single root node",
+            )),
+            (None, None) => Err(ParseError::whole("RSX should contain a single root node")),
+        }
+    }
+
+    fn parse_element(
+        &self,
+        element: &NodeElement,
+    ) -> Result<(Constraint, Box<dyn View<B>>), ParseError> {
+        let name = element.name().to_string();
+        let (constraint, attrs) = parse_attributes(element)?;
+        let view = if let Some(component) = self.components.get(&name) {
+            let mut attrs = Attrs::new(element, attrs);
+            let view = component(&mut attrs)?;
+            attrs.finish()?;
+            view
+        } else {
+            match name.as_str() {
+                "row" | "column" | "overlay" => {
+                    let mut children = vec![];
+                    for node in &element.children {
+                        match node {
+                            Node::Element(child) => children.push(self.parse_element(child)?),
+                            Node::Comment(_) => {}
+                            node => {
+                                return Err(ParseError::new(
+                                    node.span(),
+                                    format!("`<{name}>` can only contain elements"),
+                                ))
+                            }
+                        }
+                    }
+                    let mut attrs = Attrs::new(element, attrs);
+                    let margin = attrs.u16("margin")?.unwrap_or(0);
+                    attrs.finish()?;
+                    let arrangement = match name.as_str() {
+                        "row" => Arrangement::Row,
+                        "column" => Arrangement::Column,
+                        _ => Arrangement::Overlay,
+                    };
+                    Box::new(DynLayout {
+                        arrangement,
+                        margin,
+                        children,
+                    })
+                }
+                _ => builtin(element, &name, attrs)?,
+            }
+        };
+        Ok((constraint, view))
+    }
+}
+
+/// Parses `source` with only the built-in elements.
+pub fn parse<B: Backend + 'static>(source: &str) -> Result<Box<dyn View<B>>, ParseError> {
+    ComponentRegistry::new().parse(source)
+}
+
+/// Splits the constraint off the other attributes.
+fn parse_attributes(element: &NodeElement) -> Result<(Constraint, AttrMap), ParseError> {
+    let mut constraint = Constraint::Min(0);
+    let mut attrs = AttrMap::new();
+    for node in element.attributes() {
+        let NodeAttribute::Attribute(attribute) = node else {
+            return Err(ParseError::new(
+                node.span(),
+                "attribute blocks can't be evaluated at runtime",
+            ));
+        };
+        let name = attribute.key.to_string();
+        let value = attr_value(attribute)?;
+        let size = || {
+            value
+                .as_int()
+                .and_then(|value| u16::try_from(value).ok())
+                .ok_or_else(|| {
+                    ParseError::new(attribute.span(), format!("`{name}` should be a u16"))
+                })
+        };
+        match name.as_str() {
+            "length" => constraint = Constraint::Length(size()?),
+            "min" => constraint = Constraint::Min(size()?),
+            "max" => constraint = Constraint::Max(size()?),
+            "percentage" => constraint = Constraint::Percentage(size()?),
+            _ => {
+                attrs.insert(name, value);
+            }
+        }
+    }
+    Ok((constraint, attrs))
+}
+
+fn attr_value(attribute: &KeyedAttribute) -> Result<AttrValue, ParseError> {
+    match attribute.value() {
+        Some(value) => expr_value(value),
+        None => Ok(AttrValue::Bool(true)),
+    }
+}
+
+fn expr_value(expr: &Expr) -> Result<AttrValue, ParseError> {
+    let value = match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Str(value) => AttrValue::Str(value.value()),
+            Lit::Int(value) => AttrValue::Int(
+                value
+                    .base10_parse()
+                    .map_err(|e| ParseError::new(value.span(), e.to_string()))?,
+            ),
+            Lit::Float(value) => AttrValue::Float(
+                value
+                    .base10_parse()
+                    .map_err(|e| ParseError::new(value.span(), e.to_string()))?,
+            ),
+            Lit::Bool(value) => AttrValue::Bool(value.value),
+            lit => return Err(ParseError::new(lit.span(), "unsupported literal")),
+        },
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => match expr_value(&unary.expr)? {
+            AttrValue::Int(value) => AttrValue::Int(-value),
+            AttrValue::Float(value) => AttrValue::Float(-value),
+            _ => return Err(ParseError::new(unary.span(), "only numbers can be negated")),
+        },
+        Expr::Path(path) => AttrValue::Path(
+            path.path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect::<Vec<_>>()
+                .join("::"),
+        ),
+        Expr::Binary(binary) if matches!(binary.op, BinOp::BitOr(_)) => {
+            match (expr_value(&binary.left)?, expr_value(&binary.right)?) {
+                (AttrValue::Path(left), AttrValue::Path(right)) => {
+                    AttrValue::Path(format!("{left} | {right}"))
+                }
+                _ => return Err(ParseError::new(binary.span(), "only paths can be combined")),
+            }
+        }
+        expr => {
+            return Err(ParseError::new(
+                expr.span(),
+                "only literals and paths can be evaluated at runtime",
+            ))
+        }
+    };
+    Ok(value)
+}
+
+/// Takes attributes off an [`AttrMap`] by type, so the ones left over can be
+/// reported as unknown.
+pub struct Attrs<'a> {
+    element: &'a NodeElement,
+    attrs: AttrMap,
+}
+
+impl<'a> Attrs<'a> {
+    fn new(element: &'a NodeElement, attrs: AttrMap) -> Self {
+        Self { element, attrs }
+    }
+
+    /// An error at the attribute `name`, or at the element if it has none.
+    pub fn error(&self, name: &str, message: String) -> ParseError {
+        let span = self
+            .element
+            .attributes()
+            .iter()
+            .find_map(|node| match node {
+                NodeAttribute::Attribute(attribute) if attribute.key.to_string() == name => {
+                    Some(attribute.span())
+                }
+                _ => None,
+            })
+            .unwrap_or_else(|| self.element.name().span());
+        ParseError::new(span, message)
+    }
+
+    /// Removes `name`, failing with "`name` should be `expected`" if
+    /// `convert` rejects its value.
+    pub fn take<T>(
+        &mut self,
+        name: &str,
+        expected: &str,
+        convert: impl FnOnce(&AttrValue) -> Option<T>,
+    ) -> Result<Option<T>, ParseError> {
+        match self.attrs.remove(name) {
+            Some(value) => match convert(&value) {
+                Some(value) => Ok(Some(value)),
+                None => Err(self.error(name, format!("`{name}` should be {expected}"))),
+            },
+            None => Ok(None),
+        }
+    }
+
+    pub fn string(&mut self, name: &str) -> Result<Option<String>, ParseError> {
+        self.take(name, "a string", |value| value.as_str().map(String::from))
+    }
+
+    pub fn u16(&mut self, name: &str) -> Result<Option<u16>, ParseError> {
+        self.take(name, "a u16", |value| {
+            value.as_int().and_then(|value| u16::try_from(value).ok())
+        })
+    }
+
+    pub fn float(&mut self, name: &str) -> Result<Option<f64>, ParseError> {
+        self.take(name, "a number", AttrValue::as_float)
+    }
+
+    pub fn bool(&mut self, name: &str) -> Result<Option<bool>, ParseError> {
+        self.take(name, "a bool", AttrValue::as_bool)
+    }
+
+    pub fn path<T>(
+        &mut self,
+        name: &str,
+        expected: &str,
+        convert: impl Fn(&str) -> Option<T>,
+    ) -> Result<Option<T>, ParseError> {
+        self.take(name, expected, |value| value.as_path().and_then(&convert))
+    }
+
+    /// Fails on the first attribute that wasn't taken.
+    fn finish(self) -> Result<(), ParseError> {
+        match self.attrs.keys().min() {
+            Some(name) => Err(self.error(
+                name,
+                format!("unknown attribute `{name}` on `<{}>`", self.element.name()),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The last segment of a path, if it starts with `prefix`.
+fn variant<'a>(path: &'a str, prefix: &str) -> Option<&'a str> {
+    path.strip_prefix(prefix)?.strip_prefix("::")
+}
+
+fn borders(path: &str) -> Option<Borders> {
+    path.split(" | ").try_fold(Borders::NONE, |borders, path| {
+        let border = match variant(path, "Borders")? {
+            "ALL" => Borders::ALL,
+            "NONE" => Borders::NONE,
+            "TOP" => Borders::TOP,
+            "BOTTOM" => Borders::BOTTOM,
+            "LEFT" => Borders::LEFT,
+            "RIGHT" => Borders::RIGHT,
+            _ => return None,
+        };
+        Some(borders | border)
+    })
+}
+
+fn border_type(path: &str) -> Option<BorderType> {
+    match variant(path, "BorderType")? {
+        "Plain" => Some(BorderType::Plain),
+        "Rounded" => Some(BorderType::Rounded),
+        "Double" => Some(BorderType::Double),
+        "Thick" => Some(BorderType::Thick),
+        _ => None,
+    }
+}
+
+fn alignment(path: &str) -> Option<Alignment> {
+    match variant(path, "Alignment")? {
+        "Left" => Some(Alignment::Left),
+        "Center" => Some(Alignment::Center),
+        "Right" => Some(Alignment::Right),
+        _ => None,
+    }
+}
+
+/// The `title`, `borders`, `border_type` and `title_alignment` of a block.
+fn block(attrs: &mut Attrs) -> Result<Block<'static>, ParseError> {
+    let mut block = Block::default();
+    if let Some(title) = attrs.string("title")? {
+        block = block.title(title);
+    }
+    if let Some(borders) = attrs.path("borders", "a `Borders` value", borders)? {
+        block = block.borders(borders);
+    }
+    if let Some(border_type) = attrs.path("border_type", "a `BorderType`", border_type)? {
+        block = block.border_type(border_type);
+    }
+    if let Some(alignment) = attrs.path("title_alignment", "an `Alignment`", alignment)? {
+        block = block.title_alignment(alignment);
+    }
+    Ok(block)
+}
+
+/// The text of an element's children, one line per string literal.
+fn text(element: &NodeElement) -> Result<Text<'static>, ParseError> {
+    let mut lines = vec![];
+    for node in &element.children {
+        match node {
+            Node::Text(text) => lines.push(text.value_string()),
+            Node::Comment(_) => {}
+            node => {
+                return Err(ParseError::new(
+                    node.span(),
+                    format!("`<{}>` can only contain text", element.name()),
+                ))
+            }
+        }
+    }
+    Ok(Text::from(lines.join("\n")))
+}
+
+fn builtin<B: Backend + 'static>(
+    element: &NodeElement,
+    name: &str,
+    attrs: AttrMap,
+) -> Result<Box<dyn View<B>>, ParseError> {
+    let mut attrs = Attrs::new(element, attrs);
+    let view = match name {
+        "block" => widget(block(&mut attrs)?),
+        "paragraph" => {
+            let mut paragraph = Paragraph::new(text(element)?);
+            if let Some(alignment) = attrs.path("alignment", "an `Alignment`", alignment)? {
+                paragraph = paragraph.alignment(alignment);
+            }
+            widget(paragraph)
+        }
+        "gauge" => {
+            let mut gauge = Gauge::default();
+            if let Some(percent) = attrs.u16("percent")? {
+                if percent > 100 {
+                    return Err(attrs.error("percent", "`percent` should be 100 or less".into()));
+                }
+                gauge = gauge.percent(percent);
+            }
+            if let Some(ratio) = attrs.float("ratio")? {
+                if !(0.0..=1.0).contains(&ratio) {
+                    return Err(attrs.error("ratio", "`ratio` should be between 0 and 1".into()));
+                }
+                gauge = gauge.ratio(ratio);
+            }
+            if let Some(label) = attrs.string("label")? {
+                gauge = gauge.label(label);
+            }
+            widget(gauge)
+        }
+        "clear" => widget(Clear),
+        _ => {
+            return Err(ParseError::new(
+                element.name().span(),
+                format!("unknown element `<{name}>`"),
+            ))
+        }
+    };
+    attrs.finish()?;
+    Ok(view)
+}
+
+fn widget<B, W>(widget: W) -> Box<dyn View<B>>
+where
+    B: Backend + 'static,
+    W: ratatui::widgets::Widget + 'static,
+{
+    Box::new(move |frame: &mut Frame<B>, rect: Rect| frame.render_widget(&widget, rect))
+}
+
+/// How a [`DynLayout`] places its children: split along a direction like a
+/// `<row>` or `<column>`, or stacked like an `<overlay>`.
+enum Arrangement {
+    Row,
+    Column,
+    Overlay,
+}
+
+struct DynLayout<B: Backend> {
+    arrangement: Arrangement,
+    margin: u16,
+    children: Vec<(Constraint, Box<dyn View<B>>)>,
+}
+
+impl<B: Backend> DynLayout<B> {
+    fn split(&self, direction: Direction, rect: Rect) -> Vec<Rect> {
+        let constraints: Vec<_> = self
+            .children
+            .iter()
+            .map(|(constraint, _)| *constraint)
+            .collect();
+        Layout::default()
+            .direction(direction)
+            .margin(self.margin)
+            .constraints(constraints)
+            .split(rect)
+            .to_vec()
+    }
+}
+
+impl<B: Backend + 'static> View<B> for DynLayout<B> {
+    fn view(&mut self, frame: &mut Frame<B>, rect: Rect) {
+        let rects = match self.arrangement {
+            Arrangement::Row => self.split(Direction::Horizontal, rect),
+            Arrangement::Column => self.split(Direction::Vertical, rect),
+            Arrangement::Overlay => vec![rect; self.children.len()],
+        };
+        for ((_, child), rect) in self.children.iter_mut().zip(rects) {
+            child.view(frame, rect);
+        }
+    }
+
+    fn into_boxed_view(self) -> Box<dyn View<B>> {
+        Box::new(self)
+    }
+}
+
+/// A view loaded from a file that's reloaded when the file changes. The
+/// file's modification time is checked at most once per `interval` while
+/// rendering. If reloading fails the previous view is kept and the error is
+/// available from [`FileView::error`]; if there's no previous view the error
+/// is drawn instead. The modification time is only recorded once the file
+/// loads, so a file that failed, e.g. because it was half written, is read
+/// again on the next check.
+pub struct FileView<B: Backend> {
+    path: PathBuf,
+    components: ComponentRegistry<B>,
+    interval: Duration,
+    checked: Instant,
+    modified: Option<SystemTime>,
+    view: Option<Box<dyn View<B>>>,
+    error: Option<String>,
+}
+
+impl<B: Backend + 'static> FileView<B> {
+    pub fn new(path: impl Into<PathBuf>, components: ComponentRegistry<B>) -> Self {
+        let mut view = Self {
+            path: path.into(),
+            components,
+            interval: Duration::from_millis(500),
+            checked: clock::now(),
+            modified: None,
+            view: None,
+            error: None,
+        };
+        view.reload();
+        view
+    }
+
+    /// How often to check the file for changes.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Why the file couldn't be loaded the last time it changed.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Reloads the file if it changed since it was last loaded. Returns
+    /// whether it was reloaded successfully.
+    pub fn reload(&mut self) -> bool {
+        self.checked = clock::now();
+        let modified = fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if modified.is_some() && modified == self.modified {
+            return false;
+        }
+        let result = fs::read_to_string(&self.path)
+            .map_err(|e| format!("{}: {e}", self.path.display()))
+            .and_then(|source| {
+                self.components
+                    .parse(&source)
+                    .map_err(|e| format!("{}:{e}", self.path.display()))
+            });
+        match result {
+            Ok(view) => {
+                self.view = Some(view);
+                self.error = None;
+                self.modified = modified;
+                true
+            }
+            Err(error) => {
+                self.error = Some(error);
+                false
+            }
+        }
+    }
+}
+
+impl<B: Backend + 'static> View<B> for FileView<B> {
+    fn view(&mut self, frame: &mut Frame<B>, rect: Rect) {
+        if clock::now().duration_since(self.checked) >= self.interval {
+            self.reload();
+        }
+        match (&mut self.view, &self.error) {
+            (Some(view), _) => view.view(frame, rect),
+            (None, Some(error)) => frame.render_widget(Paragraph::new(error.as_str()), rect),
+            (None, None) => {}
+        }
+    }
+
+    fn into_boxed_view(self) -> Box<dyn View<B>> {
+        Box::new(self)
+    }
+}
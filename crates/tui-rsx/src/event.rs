@@ -0,0 +1,377 @@
+use crate::{focus, hash_key, root::Registry, View};
+use ratatui::{backend::Backend, layout::Rect, style::Style, Frame};
+use std::{cell::RefCell, hash::Hash, rc::Rc};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyCode {
+    Backspace,
+    Enter,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Tab,
+    BackTab,
+    Delete,
+    Insert,
+    F(u8),
+    Char(char),
+    Null,
+    Esc,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct KeyModifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyEvent {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyEvent {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+}
+
+impl From<KeyCode> for KeyEvent {
+    fn from(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::default())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseEventKind {
+    Down(MouseButton),
+    Up(MouseButton),
+    Drag(MouseButton),
+    Moved,
+    ScrollDown,
+    ScrollUp,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MouseEvent {
+    pub kind: MouseEventKind,
+    pub column: u16,
+    pub row: u16,
+    pub modifiers: KeyModifiers,
+}
+
+impl MouseEvent {
+    pub fn new(kind: MouseEventKind, column: u16, row: u16) -> Self {
+        Self {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::default(),
+        }
+    }
+
+    pub(crate) fn is_within(&self, rect: Rect) -> bool {
+        self.column >= rect.x
+            && self.column < rect.x + rect.width
+            && self.row >= rect.y
+            && self.row < rect.y + rect.height
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Event {
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    Resize(u16, u16),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EventResult {
+    Consumed,
+    Ignored,
+}
+
+/// Lets event handlers return `()` (always consumed), a `bool` or an explicit
+/// [`EventResult`].
+pub trait IntoEventResult {
+    fn into_event_result(self) -> EventResult;
+}
+
+impl IntoEventResult for EventResult {
+    fn into_event_result(self) -> EventResult {
+        self
+    }
+}
+
+impl IntoEventResult for () {
+    fn into_event_result(self) -> EventResult {
+        EventResult::Consumed
+    }
+}
+
+impl IntoEventResult for bool {
+    fn into_event_result(self) -> EventResult {
+        if self {
+            EventResult::Consumed
+        } else {
+            EventResult::Ignored
+        }
+    }
+}
+
+type Handler<E> = Box<dyn FnMut(E) -> EventResult>;
+
+#[derive(Default)]
+struct Handlers {
+    on_key: Option<Handler<KeyEvent>>,
+    on_click: Option<Handler<MouseEvent>>,
+    on_scroll: Option<Handler<MouseEvent>>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Focus {
+    pub(crate) id: u64,
+    pub(crate) tab_index: Option<u16>,
+    style: Option<Style>,
+}
+
+/// The event handlers attached to an element through `on_key`, `on_click` and
+/// `on_scroll`, along with its focus settings and `key`. Clones share the
+/// same handlers.
+#[derive(Clone, Default)]
+pub struct Interaction {
+    handlers: Rc<RefCell<Handlers>>,
+    focus: Option<Focus>,
+    key: Option<u64>,
+}
+
+impl Interaction {
+    /// Records the element's rect under `key` each time it's rendered.
+    pub fn key<K: Hash + ?Sized>(mut self, key: &K) -> Self {
+        self.key = Some(hash_key(key));
+        self
+    }
+
+    pub(crate) fn key_hash(&self) -> Option<u64> {
+        self.key
+    }
+
+    pub fn focusable(mut self, id: u64) -> Self {
+        self.focus = Some(Focus {
+            id,
+            tab_index: None,
+            style: None,
+        });
+        self
+    }
+
+    /// Has no effect unless the element is focusable.
+    pub fn tab_index(mut self, tab_index: u16) -> Self {
+        if let Some(focus) = &mut self.focus {
+            focus.tab_index = Some(tab_index);
+        }
+        self
+    }
+
+    /// The style patched over the element while it's focused, so only what it
+    /// sets replaces the element's own styles. Has no effect unless the
+    /// element is focusable.
+    pub fn focused_style(mut self, style: Style) -> Self {
+        if let Some(focus) = &mut self.focus {
+            focus.style = Some(style);
+        }
+        self
+    }
+
+    pub(crate) fn focus(&self) -> Option<Focus> {
+        self.focus
+    }
+
+    pub fn on_key<R: IntoEventResult>(self, mut f: impl FnMut(KeyEvent) -> R + 'static) -> Self {
+        self.handlers.borrow_mut().on_key = Some(Box::new(move |e| f(e).into_event_result()));
+        self
+    }
+
+    pub fn on_click<R: IntoEventResult>(
+        self,
+        mut f: impl FnMut(MouseEvent) -> R + 'static,
+    ) -> Self {
+        self.handlers.borrow_mut().on_click = Some(Box::new(move |e| f(e).into_event_result()));
+        self
+    }
+
+    pub fn on_scroll<R: IntoEventResult>(
+        self,
+        mut f: impl FnMut(MouseEvent) -> R + 'static,
+    ) -> Self {
+        self.handlers.borrow_mut().on_scroll = Some(Box::new(move |e| f(e).into_event_result()));
+        self
+    }
+
+    pub(crate) fn handle_key(&self, key: KeyEvent) -> EventResult {
+        match &mut self.handlers.borrow_mut().on_key {
+            Some(on_key) => on_key(key),
+            None => EventResult::Ignored,
+        }
+    }
+
+    pub(crate) fn handle_mouse(&self, mouse: MouseEvent) -> EventResult {
+        let mut handlers = self.handlers.borrow_mut();
+        let handler = match mouse.kind {
+            MouseEventKind::Down(_) => &mut handlers.on_click,
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => &mut handlers.on_scroll,
+            _ => return EventResult::Ignored,
+        };
+        match handler {
+            Some(handler) => handler(mouse),
+            None => EventResult::Ignored,
+        }
+    }
+}
+
+/// Wraps a view that has event handlers. While rendering, it records the rect
+/// it was drawn into so the enclosing [`RootView`](crate::RootView) can
+/// hit-test mouse events against it.
+pub struct Interactive<V> {
+    view: V,
+    interaction: Interaction,
+}
+
+impl<V> Interactive<V> {
+    pub fn new(view: V, interaction: Interaction) -> Self {
+        Self { view, interaction }
+    }
+}
+
+impl<B, V> View<B> for Interactive<V>
+where
+    B: Backend,
+    V: View<B> + 'static,
+{
+    fn view(&mut self, frame: &mut Frame<B>, rect: Rect) {
+        Registry::with_current(|registry| registry.enter(rect, self.interaction.clone()));
+        self.view.view(frame, rect);
+        if let Some(Focus {
+            id,
+            style: Some(style),
+            ..
+        }) = self.interaction.focus
+        {
+            if focus::focused() == Some(id) {
+                frame.buffer_mut().set_style(rect, style);
+            }
+        }
+        Registry::with_current(|registry| registry.exit());
+    }
+
+    fn into_boxed_view(self) -> Box<dyn View<B>> {
+        Box::new(self)
+    }
+}
+
+#[cfg(feature = "crossterm")]
+mod crossterm_impl {
+    use super::*;
+    use crossterm::event as ct;
+
+    impl From<ct::KeyModifiers> for KeyModifiers {
+        fn from(modifiers: ct::KeyModifiers) -> Self {
+            Self {
+                shift: modifiers.contains(ct::KeyModifiers::SHIFT),
+                control: modifiers.contains(ct::KeyModifiers::CONTROL),
+                alt: modifiers.contains(ct::KeyModifiers::ALT),
+            }
+        }
+    }
+
+    impl TryFrom<ct::KeyCode> for KeyCode {
+        type Error = ();
+
+        fn try_from(code: ct::KeyCode) -> Result<Self, Self::Error> {
+            Ok(match code {
+                ct::KeyCode::Backspace => Self::Backspace,
+                ct::KeyCode::Enter => Self::Enter,
+                ct::KeyCode::Left => Self::Left,
+                ct::KeyCode::Right => Self::Right,
+                ct::KeyCode::Up => Self::Up,
+                ct::KeyCode::Down => Self::Down,
+                ct::KeyCode::Home => Self::Home,
+                ct::KeyCode::End => Self::End,
+                ct::KeyCode::PageUp => Self::PageUp,
+                ct::KeyCode::PageDown => Self::PageDown,
+                ct::KeyCode::Tab => Self::Tab,
+                ct::KeyCode::BackTab => Self::BackTab,
+                ct::KeyCode::Delete => Self::Delete,
+                ct::KeyCode::Insert => Self::Insert,
+                ct::KeyCode::F(n) => Self::F(n),
+                ct::KeyCode::Char(c) => Self::Char(c),
+                ct::KeyCode::Null => Self::Null,
+                ct::KeyCode::Esc => Self::Esc,
+                _ => return Err(()),
+            })
+        }
+    }
+
+    impl From<ct::MouseButton> for MouseButton {
+        fn from(button: ct::MouseButton) -> Self {
+            match button {
+                ct::MouseButton::Left => Self::Left,
+                ct::MouseButton::Right => Self::Right,
+                ct::MouseButton::Middle => Self::Middle,
+            }
+        }
+    }
+
+    impl TryFrom<ct::MouseEventKind> for MouseEventKind {
+        type Error = ();
+
+        fn try_from(kind: ct::MouseEventKind) -> Result<Self, Self::Error> {
+            Ok(match kind {
+                ct::MouseEventKind::Down(button) => Self::Down(button.into()),
+                ct::MouseEventKind::Up(button) => Self::Up(button.into()),
+                ct::MouseEventKind::Drag(button) => Self::Drag(button.into()),
+                ct::MouseEventKind::Moved => Self::Moved,
+                ct::MouseEventKind::ScrollDown => Self::ScrollDown,
+                ct::MouseEventKind::ScrollUp => Self::ScrollUp,
+                #[allow(unreachable_patterns)]
+                _ => return Err(()),
+            })
+        }
+    }
+
+    /// Key releases and events without an equivalent, such as pastes, are
+    /// rejected.
+    impl TryFrom<ct::Event> for Event {
+        type Error = ();
+
+        fn try_from(event: ct::Event) -> Result<Self, Self::Error> {
+            match event {
+                ct::Event::Key(key) if key.kind != ct::KeyEventKind::Release => Ok(Self::Key(
+                    KeyEvent::new(key.code.try_into()?, key.modifiers.into()),
+                )),
+                ct::Event::Mouse(mouse) => Ok(Self::Mouse(MouseEvent {
+                    kind: mouse.kind.try_into()?,
+                    column: mouse.column,
+                    row: mouse.row,
+                    modifiers: mouse.modifiers.into(),
+                })),
+                ct::Event::Resize(width, height) => Ok(Self::Resize(width, height)),
+                _ => Err(()),
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Focus is tracked separately for each [`RootView`](crate::RootView). These
+//! functions act on the root that is rendering or handling an event, or else
+//! the one rendered last, and do nothing outside of a root.
+
+use crate::{lifecycle, root::Registry};
+
+/// Tracks the focused element and the tab order of the last rendered frame.
+/// Elements are identified by their caller id, the same key the component
+/// cache uses.
+#[derive(Default)]
+pub(crate) struct FocusManager {
+    focused: Option<u64>,
+    order: Vec<u64>,
+}
+
+impl FocusManager {
+    pub(crate) fn focused(&self) -> Option<u64> {
+        self.focused
+    }
+
+    pub(crate) fn focus(&mut self, id: u64) {
+        self.focused = Some(id);
+    }
+
+    pub(crate) fn step(&mut self, forward: bool) -> bool {
+        if self.order.is_empty() {
+            return false;
+        }
+        let len = self.order.len();
+        let next = match self
+            .focused
+            .and_then(|id| self.order.iter().position(|o| *o == id))
+        {
+            Some(i) if forward => (i + 1) % len,
+            Some(i) => (i + len - 1) % len,
+            None if forward => 0,
+            None => len - 1,
+        };
+        self.focused = Some(self.order[next]);
+        true
+    }
+
+    /// Elements with a `tab_index` come first in ascending order, followed by
+    /// the rest in tree order.
+    pub(crate) fn set_order(&mut self, mut order: Vec<(u64, Option<u16>)>) {
+        order.sort_by_key(|(_, tab_index)| tab_index.map_or(u32::MAX, u32::from));
+        self.order = order.into_iter().map(|(id, _)| id).collect();
+    }
+}
+
+/// Whether the component currently being built or rendered is focused, for
+/// a component that's rendered as `focusable` to draw itself differently
+/// while it has focus. Always `false` outside of a component.
+pub fn is_focused() -> bool {
+    lifecycle::current_component().is_some_and(|id| focused() == Some(id))
+}
+
+pub fn focused() -> Option<u64> {
+    Registry::with_active(|registry| registry.focus.focused()).flatten()
+}
+
+pub fn focus(id: u64) {
+    Registry::with_active(|registry| registry.focus.focus(id));
+}
+
+pub fn blur() {
+    Registry::with_active(|registry| registry.focus.focused = None);
+}
+
+/// Moves focus to the next element in tab order, wrapping around. Returns
+/// `false` if nothing on screen is focusable.
+pub fn focus_next() -> bool {
+    Registry::with_active(|registry| registry.focus.step(true)).unwrap_or(false)
+}
+
+/// Moves focus to the previous element in tab order, wrapping around.
+pub fn focus_prev() -> bool {
+    Registry::with_active(|registry| registry.focus.step(false)).unwrap_or(false)
+}
@@ -0,0 +1,65 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+
+/// The row and column tracks of a `<grid>`, split from the rect it's
+/// rendered into.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Grid {
+    rows: Vec<Rect>,
+    columns: Vec<Rect>,
+}
+
+impl Grid {
+    pub fn new(
+        rect: Rect,
+        rows: impl AsRef<[Constraint]>,
+        columns: impl AsRef<[Constraint]>,
+        gap: u16,
+    ) -> Self {
+        Self {
+            rows: tracks(rect, Direction::Vertical, rows.as_ref(), gap),
+            columns: tracks(rect, Direction::Horizontal, columns.as_ref(), gap),
+        }
+    }
+
+    /// The merged rect of the cells covered by a child. Spans are clamped to
+    /// the grid, and a child placed outside of it gets an empty rect.
+    pub fn area(&self, row: usize, col: usize, row_span: usize, col_span: usize) -> Rect {
+        let (Some(rows), Some(columns)) = (
+            span(&self.rows, row, row_span),
+            span(&self.columns, col, col_span),
+        ) else {
+            return Rect::default();
+        };
+        let (top, bottom) = (rows[0], rows[rows.len() - 1]);
+        let (left, right) = (columns[0], columns[columns.len() - 1]);
+        Rect::new(
+            left.x,
+            top.y,
+            right.right() - left.x,
+            bottom.bottom() - top.y,
+        )
+    }
+}
+
+/// Splits `rect` into one track per constraint, with `gap` cells between
+/// neighbouring tracks.
+fn tracks(rect: Rect, direction: Direction, constraints: &[Constraint], gap: u16) -> Vec<Rect> {
+    let mut with_gaps = Vec::with_capacity(constraints.len() * 2);
+    for (i, constraint) in constraints.iter().enumerate() {
+        if i > 0 && gap > 0 {
+            with_gaps.push(Constraint::Length(gap));
+        }
+        with_gaps.push(*constraint);
+    }
+    let chunks = Layout::default()
+        .direction(direction)
+        .constraints(with_gaps)
+        .split(rect);
+    let step = if gap > 0 { 2 } else { 1 };
+    chunks.iter().copied().step_by(step).collect()
+}
+
+fn span(tracks: &[Rect], start: usize, len: usize) -> Option<&[Rect]> {
+    let end = start.saturating_add(len.max(1)).min(tracks.len());
+    tracks.get(start..end).filter(|tracks| !tracks.is_empty())
+}
@@ -1,9 +1,30 @@
+// Lets the macros refer to `::tui_rsx` from inside this crate as well
+extern crate self as tui_rsx;
+
+use event::{Event, EventResult};
 use prelude::*;
 use ratatui::{backend::Backend, layout::Rect, style::Style, Frame};
-use std::{cell::RefCell, collections::HashMap, marker::PhantomData, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    rc::Rc,
+};
 use typemap::Key;
 
+pub use container::{split_container, ChildMeta, LayoutContainer};
+#[cfg(feature = "debug")]
+pub use debug::{ElementInfo, Inspect};
+pub use event::{Interaction, Interactive};
+pub use grid::Grid;
 pub use once_cell;
+pub use placement::{Anchor, Placement};
+pub use ratatui;
+#[cfg(feature = "reactive")]
+pub use reactive::LazyViewWrapper;
+pub use root::{element_at, rect_of, KeyedElement, RootView};
+pub use scroll::{ScrollArea, ScrollViewState};
 pub use tui_rsx_macros::*;
 pub use typed_builder;
 pub use typemap;
@@ -12,78 +33,127 @@ pub mod prelude {
     pub use super::*;
     pub use ratatui::{layout::*, style::*, text::*, widgets::*, Frame};
 }
+pub mod clock;
 pub mod components;
-
-macro_rules! impl_widget {
-    ($name:ident, $widget:ident, $props:ident) => {
-        pub type $props<'a> = $widget<'a>;
-
-        impl MakeBuilder for $props<'_> {}
-
-        pub fn $name<T, B: Backend>(_cx: T, props: $props<'static>) -> impl View<B> {
-            move |frame: &mut Frame<B>, rect: Rect| frame.render_widget(&props, rect)
-        }
-    };
+mod container;
+#[cfg(feature = "debug")]
+pub mod debug;
+#[cfg(feature = "dynamic")]
+pub mod dynamic;
+pub mod event;
+pub mod focus;
+mod grid;
+pub mod lifecycle;
+mod placement;
+#[cfg(feature = "reactive")]
+pub mod reactive;
+mod root;
+mod scroll;
+#[cfg(feature = "testing")]
+pub mod testing;
+
+/// Renders a stateful widget with the value passed as an element's `state`.
+///
+/// There's deliberately no impl for `&mut S`. The state is moved into the
+/// element's render closure, which has to be `'static`, so a borrow could never
+/// be passed. Use an `Rc<RefCell<S>>` to see changes made during render, such
+/// as the selected item or scroll offset, outside of the view.
+pub trait StatefulRender<B, W>
+where
+    B: Backend,
+    W: StatefulWidget,
+{
+    fn render_with_state(&mut self, widget: &W, frame: &mut Frame<B>, rect: Rect);
 }
 
-macro_rules! impl_widget_no_lifetime {
-    ($name:ident, $widget:ident, $props:ident) => {
-        pub type $props = $widget;
-
-        impl MakeBuilder for $props {}
+impl<B, W, S> StatefulRender<B, W> for RefCell<S>
+where
+    B: Backend,
+    W: StatefulWidget<State = S>,
+{
+    fn render_with_state(&mut self, widget: &W, frame: &mut Frame<B>, rect: Rect) {
+        frame.render_stateful_widget(widget, rect, self.get_mut())
+    }
+}
 
-        pub fn $name<T, B: Backend>(_cx: T, props: $props) -> impl View<B> {
-            move |frame: &mut Frame<B>, rect: Rect| frame.render_widget(&props, rect)
-        }
-    };
+/// Shares the state with the caller so that changes made during render, such as
+/// scroll offsets, are visible outside of the view.
+impl<B, W, S> StatefulRender<B, W> for Rc<RefCell<S>>
+where
+    B: Backend,
+    W: StatefulWidget<State = S>,
+{
+    fn render_with_state(&mut self, widget: &W, frame: &mut Frame<B>, rect: Rect) {
+        frame.render_stateful_widget(widget, rect, &mut self.borrow_mut())
+    }
 }
 
-macro_rules! impl_stateful_widget {
-    ($name:ident, $widget:ident, $props:ident, $state:ident) => {
-        impl<'a, B> StatefulRender<B, $props<'a>> for RefCell<$state>
+macro_rules! impl_owned_state {
+    ($state:ident) => {
+        impl<B, W> StatefulRender<B, W> for $state
         where
             B: Backend,
+            W: StatefulWidget<State = $state>,
         {
-            fn render_with_state(&mut self, widget: &$props, frame: &mut Frame<B>, rect: Rect) {
-                frame.render_stateful_widget(widget, rect, &mut self.borrow_mut())
+            fn render_with_state(&mut self, widget: &W, frame: &mut Frame<B>, rect: Rect) {
+                frame.render_stateful_widget(widget, rect, self)
             }
         }
+    };
+}
 
-        impl<'a, B> StatefulRender<B, $props<'a>> for $state
-        where
-            B: Backend,
-        {
-            fn render_with_state(&mut self, widget: &$props, frame: &mut Frame<B>, rect: Rect) {
-                frame.render_stateful_widget(widget, rect, &mut self.clone())
-            }
-        }
+impl_owned_state!(ListState);
+impl_owned_state!(TableState);
+impl_owned_state!(ScrollbarState);
 
-        pub type $props<'a> = $widget<'a>;
+pub struct KeyWrapper<T>(PhantomData<T>);
 
-        pub fn $name<'a, T, B: Backend>(
-            _cx: T,
-            props: $props<'static>,
-            mut state: impl StatefulRender<B, $widget<'a>> + 'static,
-        ) -> impl View<B> {
-            move |frame: &mut Frame<B>, rect: Rect| {
-                state.render_with_state(&props, frame, rect);
-            }
-        }
-    };
+/// Derives an element's caller id from its call site, its parent's caller id
+/// and its `key`. Uses FNV-1a instead of the std hasher so ids are the same
+/// across builds and crates.
+pub fn derive_caller_id<K: Hash + ?Sized>(call_site: &str, parent_id: u64, key: &K) -> u64 {
+    let mut hasher = Fnv1a::default();
+    call_site.hash(&mut hasher);
+    parent_id.hash(&mut hasher);
+    key.hash(&mut hasher);
+    hasher.finish()
 }
 
-pub trait StatefulRender<B, W>
-where
-    B: Backend,
-    W: StatefulWidget,
-{
-    fn render_with_state(&mut self, widget: &W, frame: &mut Frame<B>, rect: Rect);
+/// Hashes an element's `key`, so keys of any type can be looked up.
+pub(crate) fn hash_key<K: Hash + ?Sized>(key: &K) -> u64 {
+    let mut hasher = Fnv1a::default();
+    key.hash(&mut hasher);
+    hasher.finish()
 }
 
-pub struct KeyWrapper<T>(PhantomData<T>);
+/// Applies a `<for>` element's `key` closure to an item.
+pub fn for_key<T, K: Hash>(item: &T, key: impl FnOnce(&T) -> K) -> K {
+    key(item)
+}
+
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
 
 impl<B: Backend + 'static> Key for KeyWrapper<B> {
-    type Value = HashMap<u32, Rc<RefCell<dyn View<B>>>>;
+    type Value = HashMap<u64, Rc<RefCell<dyn View<B>>>>;
 }
 
 pub trait BuilderFacade {
@@ -92,7 +162,7 @@ pub trait BuilderFacade {
 
 pub trait BuildFacade {
     fn build(self) -> Self;
-    fn __caller_id(self, caller_id: u32) -> Self;
+    fn __caller_id(self, caller_id: u64) -> Self;
 }
 
 pub trait MakeBuilder {}
@@ -114,7 +184,7 @@ where
         self
     }
 
-    fn __caller_id(self, _caller_id: u32) -> Self {
+    fn __caller_id(self, _caller_id: u64) -> Self {
         self
     }
 }
@@ -125,28 +195,75 @@ impl<'a> MakeBuilder for Span<'a> {}
 impl<'a> MakeBuilder for ListItem<'a> {}
 impl<'a> MakeBuilder for Line<'a> {}
 impl<'a> MakeBuilder for Text<'a> {}
+impl<'a> MakeBuilder for Bar<'a> {}
+impl<'a> MakeBuilder for BarGroup<'a> {}
+impl<'a> MakeBuilder for Dataset<'a> {}
+impl<'a> MakeBuilder for Axis<'a> {}
 impl MakeBuilder for Style {}
 impl MakeBuilder for ListState {}
 impl MakeBuilder for TableState {}
 impl MakeBuilder for Wrap {}
 
-impl_widget!(block, Block, BlockProps);
-impl_widget!(paragraph, Paragraph, ParagraphProps);
-impl_widget!(list, List, ListProps);
-impl_widget!(tabs, Tabs, TabsProps);
-impl_widget!(table, Table, TableProps);
-impl_widget_no_lifetime!(clear, Clear, ClearProps);
-impl_stateful_widget!(stateful_list, List, StatefulListProps, ListState);
-impl_stateful_widget!(stateful_table, Table, StatefulTableProps, TableState);
-impl_stateful_widget!(
-    stateful_scrollbar,
-    Scrollbar,
-    StatefulScrollbarProps,
-    ScrollbarState
-);
+#[widget]
+pub type BlockProps<'a> = Block<'a>;
+
+#[widget]
+pub type ParagraphProps<'a> = Paragraph<'a>;
+
+#[widget]
+pub type ListProps<'a> = List<'a>;
+
+#[widget]
+pub type TabsProps<'a> = Tabs<'a>;
+
+#[widget]
+pub type TableProps<'a> = Table<'a>;
+
+#[widget]
+pub type GaugeProps<'a> = Gauge<'a>;
+
+#[widget]
+pub type LineGaugeProps<'a> = LineGauge<'a>;
+
+#[widget]
+pub type SparklineProps<'a> = Sparkline<'a>;
+
+#[widget]
+pub type BarChartProps<'a> = BarChart<'a>;
+
+#[widget]
+pub type ChartProps<'a> = Chart<'a>;
+
+#[widget]
+pub type ClearProps = Clear;
+
+#[stateful_widget(skip_make_builder)]
+pub type StatefulListProps<'a> = List<'a>;
+
+#[stateful_widget(skip_make_builder)]
+pub type StatefulTableProps<'a> = Table<'a>;
+
+#[stateful_widget]
+pub type StatefulScrollbarProps<'a> = Scrollbar<'a>;
+
+pub type CanvasProps<'a, F> = Canvas<'a, F>;
+
+impl<'a, F> MakeBuilder for CanvasProps<'a, F> where F: Fn(&mut canvas::Context) {}
+
+pub fn canvas<T, B: Backend, F>(_cx: T, props: CanvasProps<'static, F>) -> impl View<B>
+where
+    F: Fn(&mut canvas::Context) + 'static,
+{
+    move |frame: &mut Frame<B>, rect: Rect| frame.render_widget(&props, rect)
+}
 
 pub trait View<B: Backend> {
     fn view(&mut self, frame: &mut Frame<B>, rect: Rect);
+    /// Only a [`RootView`] handles events. It dispatches them to the handlers
+    /// its elements registered in the last frame, so other views ignore them.
+    fn handle_event(&mut self, _event: &Event, _rect: Rect) -> EventResult {
+        EventResult::Ignored
+    }
     fn into_boxed_view(self) -> Box<dyn View<B>>;
 }
 
@@ -172,6 +289,10 @@ where
         (**self).view(frame, rect)
     }
 
+    fn handle_event(&mut self, event: &Event, rect: Rect) -> EventResult {
+        (**self).handle_event(event, rect)
+    }
+
     fn into_boxed_view(self) -> Box<dyn View<B>> {
         self
     }
@@ -182,6 +303,10 @@ impl<B: Backend + 'static> View<B> for Rc<RefCell<dyn View<B>>> {
         self.borrow_mut().view(frame, rect)
     }
 
+    fn handle_event(&mut self, event: &Event, rect: Rect) -> EventResult {
+        self.borrow_mut().handle_event(event, rect)
+    }
+
     fn into_boxed_view(self) -> Box<dyn View<B>> {
         Box::new(self)
     }
@@ -192,13 +317,77 @@ impl<B: Backend + 'static, V: View<B> + 'static> View<B> for Rc<RefCell<V>> {
         self.borrow_mut().view(frame, rect)
     }
 
+    fn handle_event(&mut self, event: &Event, rect: Rect) -> EventResult {
+        self.borrow_mut().handle_event(event, rect)
+    }
+
     fn into_boxed_view(self) -> Box<dyn View<B>> {
         Box::new(self)
     }
 }
 
+/// An empty view, e.g. a `<show>` without a `fallback`.
+impl<B: Backend> View<B> for () {
+    fn view(&mut self, _frame: &mut Frame<B>, _rect: Rect) {}
+
+    fn into_boxed_view(self) -> Box<dyn View<B>> {
+        Box::new(self)
+    }
+}
+
+macro_rules! impl_either {
+    ($name:ident, $($variant:ident($view:ident)),+) => {
+        /// One branch of a `<show>`, `<switch>`, or an `if` or `match` in a
+        /// `{}` child whose arms produce views, so the branches can have
+        /// different view types without boxing.
+        pub enum $name<$($view),+> {
+            $($variant($view)),+
+        }
+
+        impl<B, $($view),+> View<B> for $name<$($view),+>
+        where
+            B: Backend,
+            $($view: View<B> + 'static),+
+        {
+            fn view(&mut self, frame: &mut Frame<B>, rect: Rect) {
+                match self {
+                    $(Self::$variant(view) => view.view(frame, rect)),+
+                }
+            }
+
+            fn into_boxed_view(self) -> Box<dyn View<B>> {
+                Box::new(self)
+            }
+        }
+    };
+}
+
+impl_either!(Either2, A(V1), B(V2));
+impl_either!(Either3, A(V1), B(V2), C(V3));
+impl_either!(Either4, A(V1), B(V2), C(V3), D(V4));
+impl_either!(Either5, A(V1), B(V2), C(V3), D(V4), E(V5));
+impl_either!(Either6, A(V1), B(V2), C(V3), D(V4), E(V5), F(V6));
+impl_either!(Either7, A(V1), B(V2), C(V3), D(V4), E(V5), F(V6), G(V7));
+impl_either!(
+    Either8,
+    A(V1),
+    B(V2),
+    C(V3),
+    D(V4),
+    E(V5),
+    F(V6),
+    G(V7),
+    H(V8)
+);
+
 pub trait LazyView<B: Backend> {
-    fn view(&mut self, frame: &mut Frame<B>, rect: Rect);
+    type View: View<B>;
+
+    fn build(&mut self) -> Self::View;
+
+    fn view(&mut self, frame: &mut Frame<B>, rect: Rect) {
+        self.build().view(frame, rect)
+    }
 }
 
 impl<B: Backend, F, Ret> LazyView<B> for F
@@ -206,11 +395,14 @@ where
     F: FnMut() -> Ret,
     Ret: View<B>,
 {
-    fn view(&mut self, frame: &mut Frame<B>, rect: Rect) {
-        (self)().view(frame, rect)
+    type View = Ret;
+
+    fn build(&mut self) -> Ret {
+        (self)()
     }
 }
 
+#[cfg(not(feature = "reactive"))]
 pub struct LazyViewWrapper<B, F>
 where
     B: Backend,
@@ -220,6 +412,7 @@ where
     _phantom: PhantomData<B>,
 }
 
+#[cfg(not(feature = "reactive"))]
 impl<B, F> LazyViewWrapper<B, F>
 where
     B: Backend,
@@ -233,6 +426,7 @@ where
     }
 }
 
+#[cfg(not(feature = "reactive"))]
 impl<B, F> View<B> for LazyViewWrapper<B, F>
 where
     B: Backend + 'static,
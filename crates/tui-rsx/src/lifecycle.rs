@@ -0,0 +1,191 @@
+use crate::{root::Registry, View};
+use ratatui::{backend::Backend, layout::Rect, Frame};
+use std::{
+    any::TypeId,
+    cell::RefCell,
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+thread_local! {
+    static MOUNTED: RefCell<HashMap<Key, Entry>> = RefCell::new(HashMap::new());
+    static BUILDING: RefCell<Vec<Hooks>> = const { RefCell::new(Vec::new()) };
+    static CURRENT: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Identifies a cached component instance: the component, the backend it
+/// was cached for and its caller id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Key {
+    cache: TypeId,
+    component: &'static str,
+    caller_id: u64,
+}
+
+impl Key {
+    pub fn new<C: 'static>(component: &'static str, caller_id: u64) -> Self {
+        Self {
+            cache: TypeId::of::<C>(),
+            component,
+            caller_id,
+        }
+    }
+}
+
+type Hook = Box<dyn FnOnce()>;
+
+#[derive(Default)]
+struct Hooks {
+    on_mount: Vec<Hook>,
+    on_unmount: Vec<Hook>,
+}
+
+struct Entry {
+    marked: bool,
+    mounted: bool,
+    /// The root that last rendered the component, the only one that sweeps it
+    /// while it exists.
+    root: Weak<RefCell<Registry>>,
+    evict: Hook,
+    hooks: Hooks,
+}
+
+/// Runs `f` the first time the component currently being built is rendered.
+/// Must be called from a component body.
+pub fn on_mount(f: impl FnOnce() + 'static) {
+    BUILDING.with(|b| match b.borrow_mut().last_mut() {
+        Some(hooks) => hooks.on_mount.push(Box::new(f)),
+        None => panic!("on_mount must be called from a component body"),
+    });
+}
+
+/// Runs `f` when the component currently being built is evicted from its
+/// cache. Must be called from a component body.
+pub fn on_unmount(f: impl FnOnce() + 'static) {
+    BUILDING.with(|b| match b.borrow_mut().last_mut() {
+        Some(hooks) => hooks.on_unmount.push(Box::new(f)),
+        None => panic!("on_unmount must be called from a component body"),
+    });
+}
+
+/// The caller id of the component currently being built or rendered, the
+/// innermost one if components are nested. A component that's `focusable` is
+/// focused under this id, see [`focus::is_focused`](crate::focus::is_focused).
+pub fn current_component() -> Option<u64> {
+    CURRENT.with(|c| c.borrow().last().copied())
+}
+
+fn with_component<T>(key: &Key, f: impl FnOnce() -> T) -> T {
+    CURRENT.with(|c| c.borrow_mut().push(key.caller_id));
+    let res = f();
+    CURRENT.with(|c| c.borrow_mut().pop());
+    res
+}
+
+/// Like [`on_unmount`], but does nothing outside of a component body.
+#[cfg(feature = "reactive")]
+pub(crate) fn on_unmount_if_building(f: impl FnOnce() + 'static) {
+    BUILDING.with(|b| {
+        if let Some(hooks) = b.borrow_mut().last_mut() {
+            hooks.on_unmount.push(Box::new(f));
+        }
+    });
+}
+
+fn mark(key: &Key) {
+    let root = Registry::current();
+    let on_mount = MOUNTED.with(|m| {
+        let mut mounted = m.borrow_mut();
+        let entry = mounted.get_mut(key)?;
+        entry.marked = true;
+        if let Some(root) = &root {
+            entry.root = Rc::downgrade(root);
+        }
+        if entry.mounted {
+            return None;
+        }
+        entry.mounted = true;
+        Some(std::mem::take(&mut entry.hooks.on_mount))
+    });
+    for hook in on_mount.into_iter().flatten() {
+        hook();
+    }
+}
+
+/// Evicts the components of `root` that weren't rendered since its previous
+/// sweep and runs their `on_unmount` hooks. Each [`RootView`](crate::RootView)
+/// sweeps after it's rendered. Components that were never rendered, or whose
+/// root was dropped, are swept by whichever root sweeps next.
+pub(crate) fn sweep(root: &Rc<RefCell<Registry>>) {
+    let root = Rc::downgrade(root);
+    let owned = |entry: &Entry| entry.root.strong_count() == 0 || entry.root.ptr_eq(&root);
+    let evicted: Vec<_> = MOUNTED.with(|m| {
+        let mut mounted = m.borrow_mut();
+        let stale: Vec<_> = mounted
+            .iter()
+            .filter(|(_, entry)| owned(entry) && !entry.marked)
+            .map(|(key, _)| *key)
+            .collect();
+        mounted
+            .values_mut()
+            .filter(|entry| owned(entry))
+            .for_each(|entry| entry.marked = false);
+        stale
+            .into_iter()
+            .filter_map(|key| mounted.remove(&key))
+            .collect()
+    });
+    for entry in evicted {
+        (entry.evict)();
+        for hook in entry.hooks.on_unmount {
+            hook();
+        }
+    }
+}
+
+/// A cached component. Rendering it marks it as mounted for the current frame.
+pub struct Mounted<V> {
+    key: Key,
+    view: V,
+}
+
+impl<V> Mounted<V> {
+    /// Builds the component with `build`, collecting any hooks it registers.
+    /// `evict` removes it from its cache once it stops being rendered.
+    pub fn new(key: Key, evict: impl FnOnce() + 'static, build: impl FnOnce() -> V) -> Self {
+        BUILDING.with(|b| b.borrow_mut().push(Hooks::default()));
+        let view = with_component(&key, build);
+        let hooks = BUILDING.with(|b| b.borrow_mut().pop()).unwrap_or_default();
+        MOUNTED.with(|m| {
+            m.borrow_mut().insert(
+                key,
+                Entry {
+                    // Components built outside of a frame survive the next sweep
+                    marked: true,
+                    mounted: false,
+                    root: Registry::current()
+                        .as_ref()
+                        .map_or_else(Weak::new, Rc::downgrade),
+                    evict: Box::new(evict),
+                    hooks,
+                },
+            )
+        });
+        Self { key, view }
+    }
+}
+
+impl<B, V> View<B> for Mounted<V>
+where
+    B: Backend,
+    V: View<B> + 'static,
+{
+    fn view(&mut self, frame: &mut Frame<B>, rect: Rect) {
+        mark(&self.key);
+        with_component(&self.key, || self.view.view(frame, rect));
+    }
+
+    fn into_boxed_view(self) -> Box<dyn View<B>> {
+        Box::new(self)
+    }
+}
@@ -0,0 +1,132 @@
+use ratatui::layout::{Constraint, Rect};
+
+/// The point of an `<overlay>` a placed child is positioned from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+#[derive(Clone, Copy)]
+enum Align {
+    Start,
+    Center,
+    End,
+}
+
+impl Anchor {
+    fn align(self) -> (Align, Align) {
+        match self {
+            Anchor::TopLeft => (Align::Start, Align::Start),
+            Anchor::Top => (Align::Center, Align::Start),
+            Anchor::TopRight => (Align::End, Align::Start),
+            Anchor::Left => (Align::Start, Align::Center),
+            Anchor::Center => (Align::Center, Align::Center),
+            Anchor::Right => (Align::End, Align::Center),
+            Anchor::BottomLeft => (Align::Start, Align::End),
+            Anchor::Bottom => (Align::Center, Align::End),
+            Anchor::BottomRight => (Align::End, Align::End),
+        }
+    }
+}
+
+/// Where a child of an `<overlay>` is drawn, set with its `place:x`, `place:y`,
+/// `place:width`, `place:height`, `place:width_percent`, `place:height_percent`,
+/// `place:anchor`, `place:offset_x` and `place:offset_y` attributes.
+///
+/// The size defaults to the whole overlay. `x` and `y` are relative to the
+/// overlay's top left corner and take precedence over the anchor. Offsets
+/// are applied last, and the result is clamped to the overlay.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Placement {
+    x: Option<u16>,
+    y: Option<u16>,
+    width: Option<Constraint>,
+    height: Option<Constraint>,
+    anchor: Anchor,
+    offset_x: i16,
+    offset_y: i16,
+}
+
+impl Placement {
+    pub fn x(mut self, x: u16) -> Self {
+        self.x = Some(x);
+        self
+    }
+
+    pub fn y(mut self, y: u16) -> Self {
+        self.y = Some(y);
+        self
+    }
+
+    pub fn width(mut self, width: u16) -> Self {
+        self.width = Some(Constraint::Length(width));
+        self
+    }
+
+    pub fn height(mut self, height: u16) -> Self {
+        self.height = Some(Constraint::Length(height));
+        self
+    }
+
+    pub fn width_percent(mut self, percent: u16) -> Self {
+        self.width = Some(Constraint::Percentage(percent.min(100)));
+        self
+    }
+
+    pub fn height_percent(mut self, percent: u16) -> Self {
+        self.height = Some(Constraint::Percentage(percent.min(100)));
+        self
+    }
+
+    pub fn anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    pub fn offset_x(mut self, offset: i16) -> Self {
+        self.offset_x = offset;
+        self
+    }
+
+    pub fn offset_y(mut self, offset: i16) -> Self {
+        self.offset_y = offset;
+        self
+    }
+
+    /// The rect of the child within `parent`.
+    pub fn area(&self, parent: Rect) -> Rect {
+        let (align_x, align_y) = self.anchor.align();
+        let (x, width) = place(parent.width, self.width, self.x, align_x, self.offset_x);
+        let (y, height) = place(parent.height, self.height, self.y, align_y, self.offset_y);
+        Rect::new(parent.x + x, parent.y + y, width, height)
+    }
+}
+
+/// Places a child along one axis of a parent of size `available`, returning
+/// its start relative to the parent and its size.
+fn place(
+    available: u16,
+    size: Option<Constraint>,
+    start: Option<u16>,
+    align: Align,
+    offset: i16,
+) -> (u16, u16) {
+    let size = size.map_or(available, |size| size.apply(available).min(available));
+    let free = available - size;
+    let start = start.unwrap_or(match align {
+        Align::Start => 0,
+        Align::Center => free / 2,
+        Align::End => free,
+    });
+    let start = (i32::from(start) + i32::from(offset)).clamp(0, i32::from(free));
+    (start as u16, size)
+}
@@ -0,0 +1,172 @@
+use crate::{lifecycle, LazyView, View};
+use leptos_reactive::{create_effect, Scope, ScopeDisposer};
+use ratatui::{backend::Backend, layout::Rect, Frame, Terminal};
+use std::{
+    cell::{Cell, RefCell},
+    io,
+    marker::PhantomData,
+    rc::Rc,
+};
+
+thread_local! {
+    static SCOPE: Cell<Option<Scope>> = const { Cell::new(None) };
+    static DIRTY: Cell<bool> = const { Cell::new(true) };
+    static DRAW_SCOPE: RefCell<Option<ScopeDisposer>> = const { RefCell::new(None) };
+}
+
+/// Forces the next [`draw_if_dirty`] to redraw, e.g. after a resize.
+pub fn mark_dirty() {
+    DIRTY.with(|d| d.set(true));
+}
+
+pub fn is_dirty() -> bool {
+    DIRTY.with(|d| d.get())
+}
+
+/// Draws the frame if a signal read since the last draw changed. That covers
+/// signals read while building a component, which start being tracked in a
+/// child scope of `cx` the first time the component is rendered, as well as
+/// those read while rendering, such as `<show when>` conditions, `<for each>`
+/// items and blocks. Returns whether a frame was drawn.
+pub fn draw_if_dirty<B, F>(cx: Scope, terminal: &mut Terminal<B>, f: F) -> io::Result<bool>
+where
+    B: Backend,
+    F: FnOnce(&mut Frame<B>),
+{
+    if !DIRTY.with(|d| d.replace(false)) {
+        return Ok(false);
+    }
+    let prev = SCOPE.with(|s| s.replace(Some(cx)));
+    let res = draw_tracked(cx, || terminal.draw(f).map(|_| ()));
+    SCOPE.with(|s| s.set(prev));
+    res.map(|_| true)
+}
+
+type Draw<'a> = Box<dyn FnOnce() -> io::Result<()> + 'a>;
+
+/// Runs `draw` inside an effect, so that the signals it reads mark the tree
+/// dirty when they change. The effect only draws on its first run and is
+/// replaced by the next draw, since what's read can change between frames.
+fn draw_tracked(cx: Scope, draw: impl FnOnce() -> io::Result<()>) -> io::Result<()> {
+    if let Some(scope) = DRAW_SCOPE.with(|s| s.take()) {
+        scope.dispose();
+    }
+    let draw: Draw<'_> = Box::new(draw);
+    // SAFETY: the effect can only call `draw` while it's in `slot`, and it's
+    // taken out before this function returns, so it's never called or
+    // dropped after what it borrows
+    let draw = unsafe { std::mem::transmute::<Draw<'_>, Draw<'static>>(draw) };
+    let slot = Rc::new(RefCell::new(Some(draw)));
+    let res = Rc::new(RefCell::new(None));
+    let scope = cx.child_scope({
+        let (slot, res) = (slot.clone(), res.clone());
+        move |cx| {
+            create_effect(cx, move |_: Option<()>| {
+                let draw = slot.borrow_mut().take();
+                match draw {
+                    Some(draw) => *res.borrow_mut() = Some(draw()),
+                    None => mark_dirty(),
+                }
+            });
+        }
+    });
+    DRAW_SCOPE.with(|s| *s.borrow_mut() = Some(scope));
+    // Effects run as they're created, but if this one didn't, draw untracked
+    let draw = slot.borrow_mut().take();
+    let res = res.borrow_mut().take();
+    match (draw, res) {
+        (Some(draw), _) => draw(),
+        (None, res) => res.unwrap_or(Ok(())),
+    }
+}
+
+/// Wraps a component's [`LazyView`]. When rendered through [`draw_if_dirty`],
+/// the view is built inside a leptos effect so that it's only rebuilt when a
+/// signal it read changes. Otherwise it's rebuilt every frame. The effect runs
+/// in its own child scope, which is disposed when the component is unmounted.
+pub struct LazyViewWrapper<B, F>
+where
+    B: Backend,
+    F: LazyView<B>,
+{
+    f: Rc<RefCell<F>>,
+    view: Rc<RefCell<Option<F::View>>>,
+    scope: Rc<RefCell<Option<ScopeDisposer>>>,
+    tracked: bool,
+    _phantom: PhantomData<B>,
+}
+
+impl<B, F> LazyViewWrapper<B, F>
+where
+    B: Backend,
+    F: LazyView<B>,
+{
+    pub fn new(f: F) -> Self {
+        let scope: Rc<RefCell<Option<ScopeDisposer>>> = Default::default();
+        lifecycle::on_unmount_if_building({
+            let scope = scope.clone();
+            move || {
+                if let Some(scope) = scope.take() {
+                    scope.dispose();
+                }
+            }
+        });
+        Self {
+            f: Rc::new(RefCell::new(f)),
+            view: Default::default(),
+            scope,
+            tracked: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn track(&mut self, cx: Scope)
+    where
+        F: 'static,
+        F::View: 'static,
+    {
+        let f = self.f.clone();
+        let view = self.view.clone();
+        let scope = cx.child_scope(|cx| {
+            create_effect(cx, move |prev: Option<()>| {
+                let built = f.borrow_mut().build();
+                *view.borrow_mut() = Some(built);
+                // The first run happens while drawing, so it doesn't need another frame
+                if prev.is_some() {
+                    mark_dirty();
+                }
+            });
+        });
+        *self.scope.borrow_mut() = Some(scope);
+        self.tracked = true;
+    }
+}
+
+impl<B, F> View<B> for LazyViewWrapper<B, F>
+where
+    B: Backend + 'static,
+    F: LazyView<B> + 'static,
+    F::View: 'static,
+{
+    fn view(&mut self, frame: &mut Frame<B>, rect: Rect) {
+        if !self.tracked {
+            if let Some(cx) = SCOPE.with(|s| s.get()) {
+                self.track(cx);
+            }
+        }
+        // Take the view out while rendering so an effect that fires mid-render
+        // can store its rebuilt view
+        let built = self.view.borrow_mut().take();
+        match built {
+            Some(mut built) => {
+                built.view(frame, rect);
+                self.view.borrow_mut().get_or_insert(built);
+            }
+            None => self.f.borrow_mut().view(frame, rect),
+        }
+    }
+
+    fn into_boxed_view(self) -> Box<dyn View<B>> {
+        Box::new(self)
+    }
+}
@@ -0,0 +1,286 @@
+use crate::{
+    event::{Event, EventResult, Interaction, KeyCode, MouseEventKind},
+    focus::FocusManager,
+    hash_key, lifecycle, scroll, View,
+};
+use ratatui::{backend::Backend, layout::Rect, Frame};
+use std::{cell::RefCell, hash::Hash, rc::Rc};
+
+thread_local! {
+    static ROOTS: RefCell<Vec<Rc<RefCell<Registry>>>> = const { RefCell::new(Vec::new()) };
+    /// The root that last finished rendering or is dispatching an event.
+    static ACTIVE: RefCell<Option<Rc<RefCell<Registry>>>> = const { RefCell::new(None) };
+}
+
+/// Where the element with `key` was drawn, in the root that is rendering or
+/// dispatching an event, or else the one rendered last. While rendering, only
+/// the elements drawn so far in the frame are known.
+pub fn rect_of<K: Hash + ?Sized>(key: &K) -> Option<Rect> {
+    Registry::with_active(|registry| registry.rect_of(hash_key(key))).flatten()
+}
+
+/// The innermost keyed element drawn over `x`, `y`, in the same root that
+/// [`rect_of`] looks in.
+pub fn element_at(x: u16, y: u16) -> Option<KeyedElement> {
+    Registry::with_active(|registry| registry.element_at(x, y)).flatten()
+}
+
+/// A keyed element and the rect it was drawn in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyedElement {
+    key: u64,
+    rect: Rect,
+}
+
+impl KeyedElement {
+    /// Whether the element was rendered with `key`.
+    pub fn is<K: Hash + ?Sized>(&self, key: &K) -> bool {
+        self.key == hash_key(key)
+    }
+
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+}
+
+struct Entry {
+    rect: Rect,
+    parent: Option<usize>,
+    interaction: Interaction,
+}
+
+/// Everything recorded while rendering a single [`RootView`], in tree order,
+/// along with the root's focus, which outlives each frame.
+#[derive(Default)]
+pub(crate) struct Registry {
+    entries: Vec<Entry>,
+    stack: Vec<usize>,
+    pub(crate) focus: FocusManager,
+}
+
+impl Registry {
+    /// The registry of the root that is currently rendering, if there is one.
+    pub(crate) fn current() -> Option<Rc<RefCell<Registry>>> {
+        ROOTS.with(|roots| roots.borrow().last().cloned())
+    }
+
+    /// Runs `f` against the registry of the root that is currently rendering,
+    /// if there is one.
+    pub(crate) fn with_current<R>(f: impl FnOnce(&mut Registry) -> R) -> Option<R> {
+        Self::current().map(|registry| f(&mut registry.borrow_mut()))
+    }
+
+    /// Runs `f` against the registry of the root that is rendering or
+    /// dispatching an event, or else the one rendered last.
+    pub(crate) fn with_active<R>(f: impl FnOnce(&mut Registry) -> R) -> Option<R> {
+        let registry = Self::current().or_else(|| ACTIVE.with(|active| active.borrow().clone()));
+        registry.map(|registry| f(&mut registry.borrow_mut()))
+    }
+
+    /// Forgets the elements of the last frame, keeping focus.
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.stack.clear();
+    }
+
+    pub(crate) fn enter(&mut self, rect: Rect, interaction: Interaction) {
+        self.entries.push(Entry {
+            rect,
+            parent: self.stack.last().copied(),
+            interaction,
+        });
+        self.stack.push(self.entries.len() - 1);
+    }
+
+    pub(crate) fn exit(&mut self) {
+        self.stack.pop();
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Moves the entries recorded since `start` up by `offset` and clips them
+    /// to `viewport`, after they were drawn into a scrolled buffer.
+    pub(crate) fn scroll(&mut self, start: usize, offset: u16, viewport: Rect) {
+        for entry in &mut self.entries[start..] {
+            entry.rect = scroll::scrolled(entry.rect, offset, viewport);
+        }
+    }
+
+    /// Returns the interactions of `index` and all of its ancestors,
+    /// innermost first.
+    fn ancestors(&self, index: usize) -> Vec<Interaction> {
+        let mut chain = vec![];
+        let mut current = Some(index);
+        while let Some(index) = current {
+            chain.push(self.entries[index].interaction.clone());
+            current = self.entries[index].parent;
+        }
+        chain
+    }
+
+    fn position_of(&self, id: u64) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| entry.interaction.focus().map(|focus| focus.id) == Some(id))
+    }
+
+    /// The rect of the last element rendered with `key`.
+    fn rect_of(&self, key: u64) -> Option<Rect> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.interaction.key_hash() == Some(key))
+            .map(|entry| entry.rect)
+    }
+
+    /// The last keyed element whose rect contains `x`, `y`. Later entries are
+    /// nested deeper or drawn on top.
+    fn element_at(&self, x: u16, y: u16) -> Option<KeyedElement> {
+        self.entries.iter().rev().find_map(|entry| {
+            let rect = entry.rect;
+            let within =
+                x >= rect.left() && x < rect.right() && y >= rect.top() && y < rect.bottom();
+            match entry.interaction.key_hash() {
+                Some(key) if within => Some(KeyedElement { key, rect }),
+                _ => None,
+            }
+        })
+    }
+
+    fn focus_order(&self) -> Vec<(u64, Option<u16>)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| entry.interaction.focus())
+            .map(|focus| (focus.id, focus.tab_index))
+            .collect()
+    }
+}
+
+fn bubble(
+    chain: Vec<Interaction>,
+    mut handle: impl FnMut(&Interaction) -> EventResult,
+) -> EventResult {
+    for interaction in &chain {
+        if handle(interaction) == EventResult::Consumed {
+            return EventResult::Consumed;
+        }
+    }
+    EventResult::Ignored
+}
+
+/// The root of a `mount!` tree. It owns the registry that elements write to
+/// while rendering and dispatches events against the last rendered frame.
+pub struct RootView<V> {
+    view: V,
+    registry: Rc<RefCell<Registry>>,
+}
+
+impl<V> RootView<V> {
+    pub fn new(view: V) -> Self {
+        Self {
+            view,
+            registry: Default::default(),
+        }
+    }
+
+    /// Where the element with `key` was drawn in the last frame.
+    pub fn rect_of<K: Hash + ?Sized>(&self, key: &K) -> Option<Rect> {
+        self.registry.borrow().rect_of(hash_key(key))
+    }
+
+    /// The innermost keyed element drawn over `x`, `y` in the last frame.
+    pub fn element_at(&self, x: u16, y: u16) -> Option<KeyedElement> {
+        self.registry.borrow().element_at(x, y)
+    }
+
+    fn dispatch(&self, event: &Event) -> EventResult {
+        // Lets handlers look up rects with the module level functions
+        ACTIVE.with(|active| *active.borrow_mut() = Some(self.registry.clone()));
+        match event {
+            Event::Key(key) => {
+                let moved = match key.code {
+                    KeyCode::Tab => self.registry.borrow_mut().focus.step(true),
+                    KeyCode::BackTab => self.registry.borrow_mut().focus.step(false),
+                    _ => false,
+                };
+                if moved {
+                    return EventResult::Consumed;
+                }
+                let chain: Vec<_> = {
+                    let registry = self.registry.borrow();
+                    match registry
+                        .focus
+                        .focused()
+                        .and_then(|id| registry.position_of(id))
+                    {
+                        Some(focused) => registry.ancestors(focused),
+                        // Later entries are nested deeper or drawn on top, so
+                        // they get the first chance to handle the key.
+                        None => registry
+                            .entries
+                            .iter()
+                            .rev()
+                            .map(|entry| entry.interaction.clone())
+                            .collect(),
+                    }
+                };
+                bubble(chain, |interaction| interaction.handle_key(*key))
+            }
+            Event::Mouse(mouse) => {
+                let chain = {
+                    let registry = self.registry.borrow();
+                    let Some(target) = registry
+                        .entries
+                        .iter()
+                        .rposition(|entry| mouse.is_within(entry.rect))
+                    else {
+                        return EventResult::Ignored;
+                    };
+                    registry.ancestors(target)
+                };
+                if let MouseEventKind::Down(_) = mouse.kind {
+                    if let Some(focus) = chain.iter().find_map(|i| i.focus()) {
+                        self.registry.borrow_mut().focus.focus(focus.id);
+                    }
+                }
+                bubble(chain, |interaction| interaction.handle_mouse(*mouse))
+            }
+            Event::Resize(..) => EventResult::Ignored,
+        }
+    }
+}
+
+impl<B, V> View<B> for RootView<V>
+where
+    B: Backend,
+    V: View<B> + 'static,
+{
+    fn view(&mut self, frame: &mut Frame<B>, rect: Rect) {
+        self.registry.borrow_mut().clear();
+        #[cfg(feature = "debug")]
+        if ROOTS.with(|roots| roots.borrow().is_empty()) {
+            crate::debug::begin_frame();
+        }
+        ROOTS.with(|roots| roots.borrow_mut().push(self.registry.clone()));
+        self.view.view(frame, rect);
+        ROOTS.with(|roots| roots.borrow_mut().pop());
+        let order = self.registry.borrow().focus_order();
+        self.registry.borrow_mut().focus.set_order(order);
+        ACTIVE.with(|active| *active.borrow_mut() = Some(self.registry.clone()));
+        lifecycle::sweep(&self.registry);
+        #[cfg(feature = "debug")]
+        if ROOTS.with(|roots| roots.borrow().is_empty()) {
+            crate::debug::end_frame(frame);
+        }
+    }
+
+    fn handle_event(&mut self, event: &Event, _rect: Rect) -> EventResult {
+        self.dispatch(event)
+    }
+
+    fn into_boxed_view(self) -> Box<dyn View<B>> {
+        Box::new(self)
+    }
+}
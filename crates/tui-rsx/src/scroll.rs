@@ -0,0 +1,161 @@
+use crate::root::Registry;
+use ratatui::{
+    backend::Backend,
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    widgets::ScrollbarState,
+    Frame,
+};
+use std::cell::RefCell;
+
+/// The scroll position of a `<scroll_view>`, along with the sizes measured
+/// the last time it was rendered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScrollViewState {
+    offset: u16,
+    content_height: u16,
+    viewport_height: u16,
+}
+
+impl ScrollViewState {
+    pub fn offset(&self) -> u16 {
+        self.offset
+    }
+
+    /// Scrolls to `offset`. It's clamped to the content the next time the
+    /// view is rendered.
+    pub fn set_offset(&mut self, offset: u16) {
+        self.offset = offset;
+    }
+
+    pub fn scroll_up(&mut self, lines: u16) {
+        self.offset = self.offset.saturating_sub(lines);
+    }
+
+    pub fn scroll_down(&mut self, lines: u16) {
+        self.offset = self.offset.saturating_add(lines).min(self.max_offset());
+    }
+
+    pub fn content_height(&self) -> u16 {
+        self.content_height
+    }
+
+    pub fn viewport_height(&self) -> u16 {
+        self.viewport_height
+    }
+
+    pub fn max_offset(&self) -> u16 {
+        self.content_height.saturating_sub(self.viewport_height)
+    }
+
+    /// The state of a `<stateful_scrollbar>` tracking this view.
+    pub fn scrollbar_state(&self) -> ScrollbarState {
+        ScrollbarState::default()
+            .content_length(self.content_height)
+            .viewport_content_length(self.viewport_height)
+            .position(self.offset)
+    }
+}
+
+/// Renders the children of a `<scroll_view>` into an off-screen buffer as
+/// tall as their content, then copies the visible window into the frame.
+pub struct ScrollArea {
+    viewport: Rect,
+    area: Rect,
+    offset: u16,
+    buffer: Buffer,
+    registered: Option<usize>,
+    #[cfg(feature = "debug")]
+    recorded: usize,
+}
+
+impl ScrollArea {
+    /// Swaps the frame's buffer for an off-screen one. The content is as tall
+    /// as `height`, or the sum of the children's constraints if it's `None`,
+    /// and at least as tall as the viewport.
+    pub fn begin<B: Backend>(
+        frame: &mut Frame<B>,
+        viewport: Rect,
+        state: &RefCell<ScrollViewState>,
+        height: Option<u16>,
+        constraints: &[Constraint],
+    ) -> Self {
+        let height = height
+            .unwrap_or_else(|| measure(constraints, viewport.height))
+            .max(viewport.height)
+            .min(u16::MAX - viewport.y);
+        let offset = {
+            let mut state = state.borrow_mut();
+            state.content_height = height;
+            state.viewport_height = viewport.height;
+            state.offset = state.offset.min(state.max_offset());
+            state.offset
+        };
+        let area = Rect { height, ..viewport };
+        let mut buffer = Buffer::empty(area);
+        std::mem::swap(frame.buffer_mut(), &mut buffer);
+        Self {
+            viewport,
+            area,
+            offset,
+            buffer,
+            registered: Registry::with_current(|registry| registry.len()),
+            #[cfg(feature = "debug")]
+            recorded: crate::debug::recorded(),
+        }
+    }
+
+    /// The rect the children are laid out in.
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+
+    /// Restores the frame's buffer and copies the visible rows into it.
+    /// Interactive and recorded children are moved to where they were drawn
+    /// on screen.
+    pub fn end<B: Backend>(mut self, frame: &mut Frame<B>) {
+        std::mem::swap(frame.buffer_mut(), &mut self.buffer);
+        let buffer = frame.buffer_mut();
+        for row in 0..self.viewport.height {
+            let y = self.viewport.y + row;
+            for x in self.viewport.left()..self.viewport.right() {
+                *buffer.get_mut(x, y) = self.buffer.get(x, y + self.offset).clone();
+            }
+        }
+        if let Some(start) = self.registered {
+            Registry::with_current(|registry| registry.scroll(start, self.offset, self.viewport));
+        }
+        #[cfg(feature = "debug")]
+        crate::debug::scroll(self.recorded, self.offset, self.viewport);
+    }
+}
+
+/// Moves `rect` from the off-screen buffer of a `<scroll_view>` scrolled by
+/// `offset` to where it's drawn on screen, clipped to `viewport`.
+pub(crate) fn scrolled(rect: Rect, offset: u16, viewport: Rect) -> Rect {
+    let top = i32::from(rect.top()) - i32::from(offset);
+    let bottom = i32::from(rect.bottom()) - i32::from(offset);
+    let top = top.max(i32::from(viewport.top()));
+    let bottom = bottom.min(i32::from(viewport.bottom()));
+    let rect = Rect {
+        y: top as u16,
+        height: (bottom - top).max(0) as u16,
+        ..rect
+    };
+    if rect.intersects(viewport) {
+        rect.intersection(viewport)
+    } else {
+        Rect::default()
+    }
+}
+
+/// The height needed to fit every child at its constraint.
+fn measure(constraints: &[Constraint], viewport: u16) -> u16 {
+    constraints
+        .iter()
+        .map(|constraint| match *constraint {
+            Constraint::Length(n) | Constraint::Min(n) | Constraint::Max(n) => n,
+            constraint => constraint.apply(viewport),
+        })
+        .fold(0, u16::saturating_add)
+}
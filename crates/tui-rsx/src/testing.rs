@@ -0,0 +1,311 @@
+//! Helpers for testing views against a [`TestBackend`].
+//!
+//! ```ignore
+//! let mut view = mount! { <block title="test" borders=Borders::ALL/> };
+//! assert_eq!(render_to_string(&mut view, 6, 2), "┌test┐\n└────┘");
+//!
+//! // Compares against tests/snapshots/block.snap
+//! assert_snapshot!("block", render_to_buffer(&mut view, 6, 2));
+//! ```
+//!
+//! Run the tests with `TUI_RSX_BLESS=1` to write new or changed snapshots
+//! instead of failing.
+//!
+//! A [`Harness`] drives a mounted view through key presses, clicks, resizes
+//! and the passing of time, re-rendering after each one.
+
+use crate::{
+    clock,
+    event::{Event, EventResult, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind},
+    KeyedElement, RootView, View,
+};
+use ratatui::{backend::TestBackend, buffer::Buffer, layout::Rect, style::Modifier, Terminal};
+use std::{
+    fmt::Write,
+    fs,
+    hash::Hash,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// The environment variable that makes [`assert_snapshot!`] write snapshots
+/// instead of comparing against them.
+pub const BLESS_VAR: &str = "TUI_RSX_BLESS";
+
+/// Renders `view` into a `width` by `height` buffer.
+pub fn render_to_buffer<V: View<TestBackend>>(view: &mut V, width: u16, height: u16) -> Buffer {
+    let mut terminal = Terminal::new(TestBackend::new(width, height))
+        .expect("creating a test terminal can't fail");
+    terminal
+        .draw(|f| view.view(f, f.size()))
+        .expect("drawing to a test terminal can't fail");
+    terminal.backend().buffer().clone()
+}
+
+/// Renders `view` and returns its symbols, one line per row.
+pub fn render_to_string<V: View<TestBackend>>(view: &mut V, width: u16, height: u16) -> String {
+    symbols(&render_to_buffer(view, width, height)).join("\n")
+}
+
+/// Owns a test terminal and a view from `mount!`, and simulates input
+/// against it. The view is rendered when the harness is created and after
+/// every step. While it exists the [`clock`] only moves on [`tick`](Self::tick).
+///
+/// ```ignore
+/// let mut harness = Harness::new(mount! { <Form/> }, 20, 5);
+/// harness.click(2, 1);
+/// harness.type_str("hello");
+/// harness.press(KeyCode::Enter);
+/// assert!(harness.text().contains("submitted"));
+/// assert_eq!(harness.rect_of("name"), Some(Rect::new(0, 1, 20, 1)));
+/// ```
+pub struct Harness<V> {
+    terminal: Terminal<TestBackend>,
+    view: RootView<V>,
+    clock: Option<Instant>,
+}
+
+impl<V: View<TestBackend> + 'static> Harness<V> {
+    pub fn new(view: RootView<V>, width: u16, height: u16) -> Self {
+        let terminal = Terminal::new(TestBackend::new(width, height))
+            .expect("creating a test terminal can't fail");
+        let clock = clock::freeze();
+        let mut harness = Self {
+            terminal,
+            view,
+            clock,
+        };
+        harness.render();
+        harness
+    }
+
+    pub fn render(&mut self) {
+        let view = &mut self.view;
+        self.terminal
+            .draw(|f| view.view(f, f.size()))
+            .expect("drawing to a test terminal can't fail");
+    }
+
+    /// Dispatches `event` to the view, then re-renders.
+    pub fn send(&mut self, event: Event) -> EventResult {
+        let size = self.size();
+        let result = self.view.handle_event(&event, size);
+        self.render();
+        result
+    }
+
+    pub fn press(&mut self, key: impl Into<KeyEvent>) -> EventResult {
+        self.send(Event::Key(key.into()))
+    }
+
+    /// Presses the key of each character in turn.
+    pub fn type_str(&mut self, text: &str) {
+        for c in text.chars() {
+            self.press(KeyCode::Char(c));
+        }
+    }
+
+    /// Presses and releases the left mouse button at `x`, `y`. Returns the
+    /// result of the press.
+    pub fn click(&mut self, x: u16, y: u16) -> EventResult {
+        let result = self.send(Event::Mouse(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Left),
+            x,
+            y,
+        )));
+        self.send(Event::Mouse(MouseEvent::new(
+            MouseEventKind::Up(MouseButton::Left),
+            x,
+            y,
+        )));
+        result
+    }
+
+    /// Resizes the terminal, sends the view a resize event and re-renders.
+    pub fn resize(&mut self, width: u16, height: u16) -> EventResult {
+        // The terminal picks up the new size when it next draws
+        self.terminal.backend_mut().resize(width, height);
+        self.send(Event::Resize(width, height))
+    }
+
+    /// Moves the [`clock`] forward by `duration` and re-renders, so that views
+    /// driven by it are drawn as they'd be after that long.
+    pub fn tick(&mut self, duration: Duration) {
+        clock::advance(duration);
+        self.render();
+    }
+
+    pub fn size(&self) -> Rect {
+        self.terminal.backend().buffer().area
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        self.terminal.backend().buffer()
+    }
+
+    /// The symbols of the last frame, one line per row.
+    pub fn text(&self) -> String {
+        symbols(self.buffer()).join("\n")
+    }
+
+    /// Where the element with `key` was drawn in the last frame.
+    pub fn rect_of<K: Hash + ?Sized>(&self, key: &K) -> Option<Rect> {
+        self.view.rect_of(key)
+    }
+
+    /// The innermost keyed element drawn over `x`, `y` in the last frame.
+    pub fn element_at(&self, x: u16, y: u16) -> Option<KeyedElement> {
+        self.view.element_at(x, y)
+    }
+
+    #[track_caller]
+    pub fn assert_buffer(&self, expected: &Buffer) {
+        self.terminal.backend().assert_buffer(expected);
+    }
+}
+
+impl<V> Drop for Harness<V> {
+    fn drop(&mut self) {
+        clock::restore(self.clock);
+    }
+}
+
+fn symbols(buffer: &Buffer) -> Vec<String> {
+    let area = buffer.area;
+    (area.top()..area.bottom())
+        .map(|y| {
+            (area.left()..area.right())
+                .map(|x| buffer.get(x, y).symbol.as_str())
+                .collect()
+        })
+        .collect()
+}
+
+/// Formats `buffer` as it's stored in a snapshot: its symbols, followed by
+/// every run of cells with a non-default style as `row:start..end` and the
+/// style.
+pub fn to_snapshot(buffer: &Buffer) -> String {
+    let mut snapshot = symbols(buffer).join("\n");
+    let mut styles = String::new();
+    let area = buffer.area;
+    for y in area.top()..area.bottom() {
+        let mut x = area.left();
+        while x < area.right() {
+            let style = cell_style(buffer, x, y);
+            let start = x;
+            while x < area.right() && cell_style(buffer, x, y) == style {
+                x += 1;
+            }
+            if let Some(style) = style {
+                let _ = writeln!(
+                    styles,
+                    "{}:{}..{} {}",
+                    y - area.y,
+                    start - area.x,
+                    x - area.x,
+                    style
+                );
+            }
+        }
+    }
+    snapshot.push('\n');
+    if !styles.is_empty() {
+        snapshot.push_str("--- styles\n");
+        snapshot.push_str(&styles);
+    }
+    snapshot
+}
+
+/// Describes the style of a cell, or `None` if it's the default.
+fn cell_style(buffer: &Buffer, x: u16, y: u16) -> Option<String> {
+    let cell = buffer.get(x, y);
+    let default = ratatui::buffer::Cell::default();
+    let mut parts = vec![];
+    if cell.fg != default.fg {
+        parts.push(format!("fg={:?}", cell.fg));
+    }
+    if cell.bg != default.bg {
+        parts.push(format!("bg={:?}", cell.bg));
+    }
+    if cell.modifier != Modifier::empty() {
+        parts.push(format!("{:?}", cell.modifier));
+    }
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+/// Compares `buffer` against the snapshot `name` in `dir`, panicking with a
+/// diff if they differ. If [`BLESS_VAR`] is set the snapshot is written
+/// instead.
+#[track_caller]
+pub fn assert_snapshot(dir: impl AsRef<Path>, name: &str, buffer: &Buffer) {
+    let path = dir.as_ref().join(format!("{name}.snap"));
+    let actual = to_snapshot(buffer);
+    if std::env::var_os(BLESS_VAR).is_some() {
+        fs::create_dir_all(dir.as_ref()).expect("failed to create the snapshot directory");
+        fs::write(&path, actual).expect("failed to write the snapshot");
+        return;
+    }
+    let Ok(expected) = fs::read_to_string(&path) else {
+        panic!(
+            "missing snapshot {}, run with {BLESS_VAR}=1 to create it\n{actual}",
+            path.display()
+        );
+    };
+    if expected != actual {
+        panic!(
+            "snapshot {} doesn't match, run with {BLESS_VAR}=1 to update it\n{}",
+            path.display(),
+            diff(&expected, &actual)
+        );
+    }
+}
+
+/// A line by line diff of two snapshots, with removed lines in red and added
+/// lines in green.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+    // Longest common subsequence, so a changed style run doesn't shift every
+    // following line out of alignment
+    let mut lcs = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            let _ = writeln!(out, "  {}", expected[i]);
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            let _ = writeln!(out, "\x1b[31m- {}\x1b[0m", expected[i]);
+            i += 1;
+        } else {
+            let _ = writeln!(out, "\x1b[32m+ {}\x1b[0m", actual[j]);
+            j += 1;
+        }
+    }
+    out
+}
+
+/// Compares a buffer against the snapshot `name` in the calling crate's
+/// `tests/snapshots` directory.
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $buffer:expr $(,)?) => {
+        $crate::testing::assert_snapshot(
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("snapshots"),
+            $name,
+            &$buffer,
+        )
+    };
+}
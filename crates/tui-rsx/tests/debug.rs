@@ -0,0 +1,79 @@
+use ratatui::{backend::TestBackend, Terminal};
+use std::{cell::RefCell, rc::Rc};
+use tui_rsx::prelude::*;
+
+#[test]
+fn debug_dump() {
+    use tui_rsx::debug;
+
+    let backend = TestBackend::new(6, 3);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut view = mount! {
+        <column>
+            <paragraph length=1 key="title">"title"</paragraph>
+            <block length=0/>
+            <paragraph>"body"</paragraph>
+        </column>
+    };
+    terminal.draw(|f| view.view(f, f.size())).unwrap();
+
+    assert_eq!(
+        debug::dump(),
+        "column min=0 0,0 6x3\n  \
+         paragraph#\"title\" length=1 0,0 6x1\n  \
+         block length=0 0,1 6x0 (empty)\n  \
+         paragraph min=0 0,1 6x2\n"
+    );
+
+    debug::set_enabled(false);
+    terminal.draw(|f| view.view(f, f.size())).unwrap();
+    assert!(debug::elements().is_empty());
+    debug::set_enabled(true);
+}
+
+#[test]
+fn debug_control_flow_and_scroll() {
+    use tui_rsx::debug;
+
+    let backend = TestBackend::new(6, 4);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let state = Rc::new(RefCell::new(ScrollViewState::default()));
+    state.borrow_mut().set_offset(2);
+    let offset = state.clone();
+    let mut view = mount! {
+        <column>
+            <show when=true length=1>
+                <paragraph>"a"</paragraph>
+            </show>
+            <switch value=1 length=1>
+                <case pattern=_>
+                    <paragraph>"b"</paragraph>
+                </case>
+            </switch>
+            <scroll_view offset=offset>
+                <for each=0..4 let:i>
+                    <paragraph length=1>{i.to_string()}</paragraph>
+                </for>
+            </scroll_view>
+        </column>
+    };
+    terminal.draw(|f| view.view(f, f.size())).unwrap();
+
+    assert_eq!(
+        debug::dump(),
+        "column min=0 0,0 6x4\n  \
+         show length=1 0,0 6x1\n    \
+         paragraph min=0 0,0 6x1\n  \
+         switch length=1 0,1 6x1\n    \
+         paragraph min=0 0,1 6x1\n  \
+         scroll_view min=0 0,2 6x2\n    \
+         for#0 length=1 0,0 0x0 (empty)\n      \
+         paragraph length=1 0,0 0x0 (empty)\n    \
+         for#1 length=1 0,0 0x0 (empty)\n      \
+         paragraph length=1 0,0 0x0 (empty)\n    \
+         for#2 length=1 0,2 6x1\n      \
+         paragraph length=1 0,2 6x1\n    \
+         for#3 length=1 0,3 6x1\n      \
+         paragraph length=1 0,3 6x1\n"
+    );
+}
@@ -0,0 +1,72 @@
+use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+use tui_rsx::prelude::*;
+
+#[test]
+fn dynamic_layout() {
+    use tui_rsx::dynamic::{parse, ComponentRegistry};
+
+    let mut components = ComponentRegistry::<TestBackend>::new();
+    components.register("Label", |attrs| {
+        let text = attrs.string("text")?.unwrap_or_default();
+        let label = move |f: &mut Frame<TestBackend>, rect: Rect| {
+            f.render_widget(Paragraph::new(text.clone()), rect)
+        };
+        Ok(label.into_boxed_view())
+    });
+    let mut view = components
+        .parse(
+            r#"
+            <column>
+                <block length=2 title="top" borders=Borders::TOP | Borders::BOTTOM/>
+                <row length=1>
+                    <paragraph percentage=50 alignment=Alignment::Right>"hi"</paragraph>
+                    <Label text="yo"/>
+                </row>
+            </column>
+            "#,
+        )
+        .unwrap();
+
+    let backend = TestBackend::new(8, 3);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal.draw(|f| view.view(f, f.size())).unwrap();
+    terminal.backend().assert_buffer(&Buffer::with_lines(vec![
+        "top─────",
+        "────────",
+        "  hiyo  ",
+    ]));
+
+    let error = parse::<TestBackend>("<column>\n  <block titel=\"x\"/>\n</column>")
+        .err()
+        .unwrap();
+    assert_eq!(
+        error.to_string(),
+        "2:10: unknown attribute `titel` on `<block>`"
+    );
+    let error = parse::<TestBackend>("<column>\n  <blok/>\n</column>")
+        .err()
+        .unwrap();
+    assert_eq!((error.line, error.column), (2, 4));
+    let error = components.parse("<Label txt=\"yo\"/>").err().unwrap();
+    assert_eq!(
+        error.to_string(),
+        "1:8: unknown attribute `txt` on `<Label>`"
+    );
+}
+
+#[test]
+fn file_view_retries_after_error() {
+    use tui_rsx::dynamic::{ComponentRegistry, FileView};
+
+    let path = std::env::temp_dir().join(format!("tui-rsx-{}.rsx", std::process::id()));
+    std::fs::write(&path, "<paragraph>").unwrap();
+    let mut view = FileView::<TestBackend>::new(&path, ComponentRegistry::new());
+    assert!(view.error().is_some());
+
+    // Likely within the same modification time as the broken version
+    std::fs::write(&path, "<paragraph>\"ok\"</paragraph>").unwrap();
+    assert!(view.reload());
+    assert_eq!(view.error(), None);
+    assert!(!view.reload());
+    std::fs::remove_file(&path).unwrap();
+}
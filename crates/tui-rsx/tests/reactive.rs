@@ -0,0 +1,74 @@
+use leptos_reactive::{create_runtime, create_scope, create_signal, ReadSignal, Scope};
+use ratatui::{
+    backend::{Backend, TestBackend},
+    buffer::Buffer,
+    Terminal,
+};
+use tui_rsx::{prelude::*, reactive::draw_if_dirty};
+
+#[test]
+fn redraws_when_show_condition_changes() {
+    create_scope(create_runtime(), |cx| {
+        let (visible, set_visible) = create_signal(cx, true);
+        let mut terminal = Terminal::new(TestBackend::new(4, 1)).unwrap();
+        let mut view = mount! { cx,
+            <column>
+                <show when=visible.get() fallback=view!(<paragraph>"no"</paragraph>)>
+                    <paragraph>"yes"</paragraph>
+                </show>
+            </column>
+        };
+
+        assert!(draw_if_dirty(cx, &mut terminal, |f| view.view(f, f.size())).unwrap());
+        terminal
+            .backend()
+            .assert_buffer(&Buffer::with_lines(vec!["yes "]));
+        assert!(!draw_if_dirty(cx, &mut terminal, |f| view.view(f, f.size())).unwrap());
+
+        set_visible.set(false);
+        assert!(draw_if_dirty(cx, &mut terminal, |f| view.view(f, f.size())).unwrap());
+        terminal
+            .backend()
+            .assert_buffer(&Buffer::with_lines(vec!["no  "]));
+        assert!(!draw_if_dirty(cx, &mut terminal, |f| view.view(f, f.size())).unwrap());
+    })
+    .dispose();
+}
+
+#[component]
+fn Toggle<B: Backend + 'static>(cx: Scope, visible: ReadSignal<bool>) -> impl View<B> {
+    move || {
+        view! { cx,
+            <column>
+                <show when=visible.get() fallback=view!(<paragraph>"off"</paragraph>)>
+                    <paragraph>"on"</paragraph>
+                </show>
+            </column>
+        }
+    }
+}
+
+#[test]
+fn redraws_when_component_show_condition_changes() {
+    create_scope(create_runtime(), |cx| {
+        let (visible, set_visible) = create_signal(cx, true);
+        let mut terminal = Terminal::new(TestBackend::new(4, 1)).unwrap();
+        let mut view = mount! { cx,
+            <column>
+                <Toggle visible=visible/>
+            </column>
+        };
+
+        assert!(draw_if_dirty(cx, &mut terminal, |f| view.view(f, f.size())).unwrap());
+        terminal
+            .backend()
+            .assert_buffer(&Buffer::with_lines(vec!["on  "]));
+
+        set_visible.set(false);
+        assert!(draw_if_dirty(cx, &mut terminal, |f| view.view(f, f.size())).unwrap());
+        terminal
+            .backend()
+            .assert_buffer(&Buffer::with_lines(vec!["off "]));
+    })
+    .dispose();
+}
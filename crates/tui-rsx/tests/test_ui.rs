@@ -4,7 +4,7 @@ use ratatui::{
     style::{Color, Style},
     Terminal,
 };
-use std::marker::PhantomData;
+use std::{cell::RefCell, marker::PhantomData, rc::Rc};
 use tui_rsx::{prelude::*, view};
 use typed_builder::TypedBuilder;
 
@@ -211,6 +211,33 @@ fn stateful() {
     ]));
 }
 
+#[test]
+fn stateful_shared() {
+    let backend = TestBackend::new(10, 1);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut state = ListState::default();
+    state.select(Some(2));
+    let state = Rc::new(RefCell::new(state));
+    let mut view = mount! {
+        <stateful_list state=state.clone()>
+            <listItem>"test1"</listItem>
+            <listItem>"test2"</listItem>
+            <listItem>"test3"</listItem>
+        </stateful_list>
+    };
+
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec!["test3     "]));
+    assert_eq!(state.borrow().offset(), 2);
+}
+
 #[test]
 fn list_styled() {
     let backend = TestBackend::new(15, 3);
@@ -357,6 +384,184 @@ fn macro_as_prop() {
     ]));
 }
 
+#[test]
+fn gauge_ratio() {
+    let backend = TestBackend::new(10, 1);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut view = mount! {
+        <column>
+            <gauge ratio=0.5/>
+        </column>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec!["   50%    "]));
+}
+
+#[test]
+fn line_gauge_ratio() {
+    let backend = TestBackend::new(10, 1);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut view = mount! {
+        <column>
+            <line_gauge ratio=0.5/>
+        </column>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec!["50% ──────"]));
+}
+
+#[test]
+fn sparkline_data() {
+    let backend = TestBackend::new(9, 1);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut view = mount! {
+        <column>
+            <sparkline data=&[0, 1, 2, 3, 4, 5, 6, 7, 8]/>
+        </column>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec![" ▁▂▃▄▅▆▇█"]));
+}
+
+#[test]
+fn bar_chart_groups() {
+    let backend = TestBackend::new(3, 3);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut view = mount! {
+        <column>
+            <bar_chart
+                data=prop!(
+                    <barGroup bars=&[
+                        prop!(<bar value=1 label=Line::from("a")/>),
+                        prop!(<bar value=2 label=Line::from("b")/>)
+                    ]/>
+                )
+            />
+        </column>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec!["  █", "1 2", "a b"]));
+}
+
+#[test]
+fn chart_datasets() {
+    let backend = TestBackend::new(3, 3);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut view = mount! {
+        <column>
+            <chart>
+                <dataset data=&[(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)]/>
+                <x_axis bounds=[0.0, 2.0]/>
+                <y_axis bounds=[0.0, 2.0]/>
+            </chart>
+        </column>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec!["  •", " • ", "•  "]));
+}
+
+#[test]
+fn canvas_shapes() {
+    let backend = TestBackend::new(4, 3);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut view = mount! {
+        <column>
+            <canvas
+                x_bounds=[0.0, 3.0]
+                y_bounds=[0.0, 2.0]
+                marker=ratatui::symbols::Marker::Block
+            >
+                <line x1=0.0 y1=2.0 x2=3.0 y2=2.0/>
+                <label x=0.0 y=0.0>"hi"</label>
+            </canvas>
+        </column>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec!["████", "    ", "hi  "]));
+}
+
+#[test]
+fn registered_widget() {
+    #[widget(skip_make_builder)]
+    type TitleBlockProps<'a> = Block<'a>;
+
+    let backend = TestBackend::new(10, 3);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut view = mount! {
+        <column>
+            <title_block title="test" borders=Borders::ALL/>
+        </column>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal.backend().assert_buffer(&Buffer::with_lines(vec![
+        "┌test────┐",
+        "│        │",
+        "└────────┘",
+    ]));
+}
+
+#[test]
+fn registered_widget_builder() {
+    #[widget(name = tab_bar, skip_make_builder, builder = Tabs::new(vec!["one".into(), "two".into()]))]
+    type TabBarProps<'a> = Tabs<'a>;
+
+    let backend = TestBackend::new(10, 1);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut view = mount! {
+        <column>
+            <tab_bar divider="|"/>
+        </column>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec![" one | two"]));
+}
+
 #[test]
 fn simple_overlay() {
     let backend = TestBackend::new(10, 3);
@@ -799,3 +1004,844 @@ fn custom_component_nested_layout() {
         .backend()
         .assert_buffer(&Buffer::with_lines(vec!["hi"]));
 }
+
+#[test]
+fn click_hit_test() {
+    use std::cell::Cell;
+    use tui_rsx::event::{Event, EventResult, MouseButton, MouseEvent, MouseEventKind};
+
+    let backend = TestBackend::new(10, 2);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let clicked = Rc::new(Cell::new(0));
+    let mut view = mount! {
+        <column>
+            <block length=1 title="a" on_click={
+                let clicked = clicked.clone();
+                move |_| clicked.set(1)
+            }/>
+            <block length=1 title="b" on_click={
+                let clicked = clicked.clone();
+                move |_| clicked.set(2)
+            }/>
+        </column>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+
+    let click = Event::Mouse(MouseEvent::new(
+        MouseEventKind::Down(MouseButton::Left),
+        3,
+        1,
+    ));
+    let rect = terminal.size().unwrap();
+    assert_eq!(view.handle_event(&click, rect), EventResult::Consumed);
+    assert_eq!(clicked.get(), 2);
+}
+
+#[test]
+fn key_bubbles_to_parent() {
+    use std::cell::Cell;
+    use tui_rsx::event::{Event, EventResult, KeyCode, KeyEvent};
+
+    let backend = TestBackend::new(10, 1);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let handled_by = Rc::new(Cell::new(""));
+    let mut view = mount! {
+        <row on_key={
+            let handled_by = handled_by.clone();
+            move |key: KeyEvent| {
+                if key.code == KeyCode::Enter {
+                    handled_by.set("row");
+                }
+            }
+        }>
+            <block on_key={|_| EventResult::Ignored} />
+        </row>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+
+    let rect = terminal.size().unwrap();
+    let result = view.handle_event(&Event::Key(KeyCode::Enter.into()), rect);
+    assert_eq!(result, EventResult::Consumed);
+    assert_eq!(handled_by.get(), "row");
+}
+
+#[test]
+fn component_handlers() {
+    use std::cell::Cell;
+    use tui_rsx::event::{
+        Event, EventResult, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind,
+    };
+
+    #[component]
+    fn Counter<T: Clone + 'static, B: Backend + 'static>(
+        cx: T,
+        count: Rc<Cell<u32>>,
+    ) -> impl View<B> {
+        move || {
+            let text = count.get().to_string();
+            let count = count.clone();
+            view! { cx,
+                <paragraph on_key={move |key: KeyEvent| {
+                    if key.code == KeyCode::Up {
+                        count.set(count.get() + 1);
+                    }
+                }}>
+                    {text}
+                </paragraph>
+            }
+        }
+    }
+
+    let backend = TestBackend::new(3, 1);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let count = Rc::new(Cell::new(0));
+    let clicked = Rc::new(Cell::new(false));
+    let mut view = mount! {
+        <Counter count={count.clone()} on_click={
+            let clicked = clicked.clone();
+            move |_| clicked.set(true)
+        }/>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+
+    let rect = terminal.size().unwrap();
+    let up = Event::Key(KeyCode::Up.into());
+    assert_eq!(view.handle_event(&up, rect), EventResult::Consumed);
+    assert_eq!(count.get(), 1);
+
+    let click = Event::Mouse(MouseEvent::new(
+        MouseEventKind::Down(MouseButton::Left),
+        1,
+        0,
+    ));
+    assert_eq!(view.handle_event(&click, rect), EventResult::Consumed);
+    assert!(clicked.get());
+
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec!["1  "]));
+}
+
+#[test]
+fn focus_traversal() {
+    use tui_rsx::event::{Event, EventResult, KeyCode};
+
+    let backend = TestBackend::new(1, 2);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut view = mount! {
+        <column>
+            <paragraph length=1 focusable focused_style={Style::default().fg(Color::Red)}>
+                "a"
+            </paragraph>
+            <paragraph length=1 focusable focused_style={Style::default().fg(Color::Red)}>
+                "b"
+            </paragraph>
+        </column>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+
+    let rect = terminal.size().unwrap();
+    let tab = Event::Key(KeyCode::Tab.into());
+    assert_eq!(view.handle_event(&tab, rect), EventResult::Consumed);
+    assert_eq!(view.handle_event(&tab, rect), EventResult::Consumed);
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+
+    let mut expected = Buffer::with_lines(vec!["a", "b"]);
+    expected.set_style(Rect::new(0, 1, 1, 1), Style::default().fg(Color::Red));
+    terminal.backend().assert_buffer(&expected);
+}
+
+#[test]
+fn focused_style_patches_style() {
+    use tui_rsx::event::{Event, KeyCode};
+
+    let backend = TestBackend::new(1, 1);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut view = mount! {
+        <paragraph
+            focusable
+            style={Style::default().bg(Color::Blue).add_modifier(Modifier::BOLD)}
+            focused_style={Style::default().fg(Color::Red)}
+        >
+            "a"
+        </paragraph>
+    };
+    terminal.draw(|f| view.view(f, f.size())).unwrap();
+    view.handle_event(&Event::Key(KeyCode::Tab.into()), terminal.size().unwrap());
+    terminal.draw(|f| view.view(f, f.size())).unwrap();
+
+    let mut expected = Buffer::with_lines(vec!["a"]);
+    expected.set_style(
+        Rect::new(0, 0, 1, 1),
+        Style::default()
+            .fg(Color::Red)
+            .bg(Color::Blue)
+            .add_modifier(Modifier::BOLD),
+    );
+    terminal.backend().assert_buffer(&expected);
+}
+
+#[test]
+fn focus_is_per_root() {
+    use tui_rsx::event::{Event, KeyCode};
+
+    let focused = Style::default().fg(Color::Red);
+    // Both roots come from the same call site, so their elements share ids
+    let make = || {
+        mount! {
+            <paragraph focusable focused_style={focused}>"a"</paragraph>
+        }
+    };
+    let mut first = make();
+    let mut second = make();
+
+    let backend = TestBackend::new(1, 1);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal.draw(|f| first.view(f, f.size())).unwrap();
+    terminal.draw(|f| second.view(f, f.size())).unwrap();
+    first.handle_event(&Event::Key(KeyCode::Tab.into()), terminal.size().unwrap());
+
+    terminal.draw(|f| second.view(f, f.size())).unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec!["a"]));
+
+    terminal.draw(|f| first.view(f, f.size())).unwrap();
+    let mut expected = Buffer::with_lines(vec!["a"]);
+    expected.set_style(Rect::new(0, 0, 1, 1), focused);
+    terminal.backend().assert_buffer(&expected);
+}
+
+#[test]
+fn component_is_focused() {
+    use tui_rsx::event::{Event, KeyCode};
+
+    #[component]
+    fn Indicator<T: Clone + 'static, B: Backend + 'static>(cx: T) -> impl View<B> {
+        move || {
+            let text = if tui_rsx::focus::is_focused() {
+                "on"
+            } else {
+                "off"
+            };
+            view! { cx,
+                <paragraph>{text}</paragraph>
+            }
+        }
+    }
+
+    let backend = TestBackend::new(3, 1);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut view = mount! {
+        <Indicator focusable/>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec!["off"]));
+
+    let rect = terminal.size().unwrap();
+    view.handle_event(&Event::Key(KeyCode::Tab.into()), rect);
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec!["on "]));
+}
+
+#[test]
+fn unmounted_component_evicted() {
+    use std::cell::Cell;
+
+    #[component]
+    fn Tracked<T: Clone + 'static, B: Backend + 'static>(
+        cx: T,
+        log: Rc<RefCell<Vec<&'static str>>>,
+    ) -> impl View<B> {
+        let mount_log = log.clone();
+        lifecycle::on_mount(move || mount_log.borrow_mut().push("mount"));
+        lifecycle::on_unmount(move || log.borrow_mut().push("unmount"));
+        move || {
+            view! { cx,
+                <block title="tracked"/>
+            }
+        }
+    }
+
+    let backend = TestBackend::new(7, 1);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let log = Rc::new(RefCell::new(vec![]));
+    let show = Rc::new(Cell::new(true));
+    let show_tracked = show.clone();
+    let tracked_log = log.clone();
+    let mut view = mount! {
+        <column>
+            {
+                if show_tracked.get() {
+                    view! {
+                        <Tracked log=tracked_log.clone()/>
+                    }.into_boxed_view()
+                } else {
+                    view! {
+                        <column/>
+                    }.into_boxed_view()
+                }
+            }
+        </column>
+    };
+
+    for visible in [true, false, true] {
+        show.set(visible);
+        terminal
+            .draw(|f| {
+                view.view(f, f.size());
+            })
+            .unwrap();
+    }
+    assert_eq!(*log.borrow(), vec!["mount", "unmount", "mount"]);
+}
+
+#[test]
+fn roots_sweep_their_own_components() {
+    #[component]
+    fn Tracked<T: Clone + 'static, B: Backend + 'static>(
+        cx: T,
+        log: Rc<RefCell<Vec<&'static str>>>,
+    ) -> impl View<B> {
+        lifecycle::on_unmount(move || log.borrow_mut().push("unmount"));
+        move || {
+            view! { cx,
+                <block title="tracked"/>
+            }
+        }
+    }
+
+    let backend = TestBackend::new(7, 2);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let log = Rc::new(RefCell::new(vec![]));
+    let mut first = mount! { <Tracked log=log.clone()/> };
+    let mut second = mount! { <Tracked log=log.clone()/> };
+
+    // Each root sweeps after it's drawn, which must not evict the other's
+    for _ in 0..3 {
+        terminal
+            .draw(|f| {
+                let top = Rect::new(0, 0, 7, 1);
+                let bottom = Rect::new(0, 1, 7, 1);
+                first.view(f, top);
+                second.view(f, bottom);
+            })
+            .unwrap();
+    }
+    assert!(log.borrow().is_empty());
+}
+
+#[test]
+fn caller_id_no_collisions() {
+    use std::collections::HashSet;
+
+    // Two call sites that differ only in their line
+    let sites = [
+        concat!(file!(), ":", line!(), "#0"),
+        concat!(file!(), ":", line!(), "#0"),
+    ];
+    let mut parents = vec![0, 1, 12];
+    parents.extend(sites.map(|site| derive_caller_id(site, 0, &())));
+
+    let mut ids = HashSet::new();
+    let mut count = 0;
+    for site in sites {
+        for &parent in &parents {
+            // Keys whose digits run together the same way, as parent ids and
+            // keys did when they were concatenated
+            ids.insert(derive_caller_id(site, parent, &3));
+            ids.insert(derive_caller_id(site, parent, &23));
+            ids.insert(derive_caller_id(site, parent, &123));
+            ids.insert(derive_caller_id(site, parent, &(1, 23)));
+            ids.insert(derive_caller_id(site, parent, &(12, 3)));
+            ids.insert(derive_caller_id(site, parent, "1"));
+            ids.insert(derive_caller_id(site, parent, "23"));
+            ids.insert(derive_caller_id(site, parent, &()));
+            count += 8;
+        }
+    }
+    assert_eq!(ids.len(), count);
+}
+
+#[test]
+fn string_keys() {
+    #[component]
+    fn Viewer<T: Clone + 'static, B: Backend + 'static>(
+        cx: T,
+        #[prop(into)] text: String,
+    ) -> impl View<B> {
+        move || {
+            view! { cx,
+                <paragraph>{text.clone()}</paragraph>
+            }
+        }
+    }
+
+    let backend = TestBackend::new(3, 2);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut view = mount! {
+        <column>
+            <Viewer length=1 key="first" text="one"/>
+            <Viewer length=1 key="second" text="two"/>
+        </column>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec!["one", "two"]));
+}
+
+#[test]
+fn for_keyed_reorder() {
+    #[component]
+    fn Item<T: Clone + 'static, B: Backend + 'static>(
+        cx: T,
+        #[prop(into)] name: String,
+    ) -> impl View<B> {
+        // Captured once per cached instance, so a mismatched key shows the
+        // wrong name
+        let first_name = name;
+        move || {
+            view! { cx,
+                <paragraph>{first_name.clone()}</paragraph>
+            }
+        }
+    }
+
+    let backend = TestBackend::new(1, 3);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let items = Rc::new(RefCell::new(vec![(1, "a"), (2, "b"), (3, "c")]));
+    let list = items.clone();
+    let mut view = mount! {
+        <column>
+            <for each=list.borrow().clone() key=|i| i.0 let:item>
+                <Item length=1 name=item.1 />
+            </for>
+            <block/>
+        </column>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec!["a", "b", "c"]));
+
+    *items.borrow_mut() = vec![(3, "c"), (1, "a")];
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec!["c", "a", " "]));
+}
+
+#[test]
+fn show_fallback() {
+    use std::cell::Cell;
+
+    let backend = TestBackend::new(4, 1);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let visible = Rc::new(Cell::new(true));
+    let when = visible.clone();
+    let mut view = mount! {
+        <column>
+            <show when=when.get() fallback=view!(<paragraph>"no"</paragraph>)>
+                <paragraph>"yes"</paragraph>
+            </show>
+        </column>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec!["yes "]));
+
+    visible.set(false);
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec!["no  "]));
+}
+
+#[test]
+fn switch_cases() {
+    use std::cell::Cell;
+
+    let backend = TestBackend::new(5, 1);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mode = Rc::new(Cell::new(0));
+    let value = mode.clone();
+    let mut view = mount! {
+        <switch value=value.get()>
+            <case pattern=0>
+                <paragraph>"zero"</paragraph>
+            </case>
+            <case pattern=1>
+                <row>
+                    <paragraph length=1>"o"</paragraph>
+                    <paragraph length=4>"ne"</paragraph>
+                </row>
+            </case>
+            <case pattern=_>
+                <paragraph>"other"</paragraph>
+            </case>
+        </switch>
+    };
+
+    for (value, expected) in [(0, "zero "), (1, "one  "), (7, "other")] {
+        mode.set(value);
+        terminal
+            .draw(|f| {
+                view.view(f, f.size());
+            })
+            .unwrap();
+        terminal
+            .backend()
+            .assert_buffer(&Buffer::with_lines(vec![expected]));
+    }
+}
+
+#[test]
+fn branch_lowering() {
+    use std::cell::Cell;
+
+    let backend = TestBackend::new(5, 2);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let state = Rc::new(Cell::new(0));
+    let (flag, mode) = (state.clone(), state.clone());
+    let mut view = mount! {
+        <column>
+            {
+                if flag.get() == 0 {
+                    view!(<paragraph length=1>"if"</paragraph>)
+                } else {
+                    view! {
+                        <row length=1>
+                            <paragraph length=2>"el"</paragraph>
+                            <paragraph>"se"</paragraph>
+                        </row>
+                    }
+                }
+            }
+            {
+                match mode.get() {
+                    0 => view!(<paragraph>"zero"</paragraph>),
+                    1 => {
+                        let text = "one";
+                        view!(<block title=text/>)
+                    }
+                    _ => view!(<column/>),
+                }
+            }
+        </column>
+    };
+
+    for (value, expected) in [
+        (0, ["if   ", "zero "]),
+        (1, ["else ", "one  "]),
+        (2, ["else ", "     "]),
+    ] {
+        state.set(value);
+        terminal
+            .draw(|f| {
+                view.view(f, f.size());
+            })
+            .unwrap();
+        terminal
+            .backend()
+            .assert_buffer(&Buffer::with_lines(expected.to_vec()));
+    }
+}
+
+#[test]
+fn grid_spans() {
+    let backend = TestBackend::new(11, 5);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut view = mount! {
+        <grid
+            rows=[Constraint::Length(2), Constraint::Min(0)]
+            columns=[Constraint::Length(3), Constraint::Length(3), Constraint::Min(0)]
+            gap=1
+        >
+            <paragraph>"a"</paragraph>
+            <paragraph col=1 col_span=2>"b"</paragraph>
+            <block row=1 col_span=3 borders=Borders::ALL/>
+        </grid>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+
+    terminal.backend().assert_buffer(&Buffer::with_lines(vec![
+        "a   b      ",
+        "           ",
+        "           ",
+        "┌─────────┐",
+        "└─────────┘",
+    ]));
+}
+
+#[test]
+fn custom_layout_container() {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    struct Sidebar {
+        collapsed: bool,
+    }
+
+    impl Sidebar {
+        fn collapsed(mut self, collapsed: bool) -> Self {
+            self.collapsed = collapsed;
+            self
+        }
+    }
+
+    impl LayoutContainer for Sidebar {
+        fn split(&self, rect: Rect, children: &[ChildMeta]) -> Vec<Rect> {
+            let width = match children[0].constraint {
+                Constraint::Length(width) if !self.collapsed => width.min(rect.width),
+                _ => 0,
+            };
+            vec![
+                Rect { width, ..rect },
+                Rect {
+                    x: rect.x + width,
+                    width: rect.width - width,
+                    ..rect
+                },
+            ]
+        }
+    }
+
+    let backend = TestBackend::new(8, 1);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let collapsed = Rc::new(Cell::new(false));
+    let is_collapsed = collapsed.clone();
+    let mut view = mount! {
+        <layout:sidebar collapsed=is_collapsed.get()>
+            <paragraph length=3>"nav"</paragraph>
+            <paragraph>"body"</paragraph>
+        </layout:sidebar>
+    };
+
+    for (value, expected) in [(false, "navbody "), (true, "body    ")] {
+        collapsed.set(value);
+        terminal
+            .draw(|f| {
+                view.view(f, f.size());
+            })
+            .unwrap();
+        terminal
+            .backend()
+            .assert_buffer(&Buffer::with_lines(vec![expected]));
+    }
+}
+
+#[test]
+fn overlay_placement() {
+    let backend = TestBackend::new(10, 5);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut view = mount! {
+        <overlay>
+            <paragraph>"base"</paragraph>
+            <block place:anchor=center place:width=4 place:height=3 borders=Borders::ALL/>
+            <paragraph
+                place:anchor=bottom_right
+                place:width=2
+                place:height=1
+                place:offset_x=-1
+            >
+                "!!"
+            </paragraph>
+            <paragraph place:x=8 place:y=0 place:width=5 place:height=1>"clamped"</paragraph>
+        </overlay>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+
+    terminal.backend().assert_buffer(&Buffer::with_lines(vec![
+        "base clamp",
+        "   ┌──┐   ",
+        "   │  │   ",
+        "   └──┘   ",
+        "       !! ",
+    ]));
+}
+
+#[test]
+fn overlay_child_with_placement_named_props() {
+    #[component]
+    fn Bar<T: Clone + 'static, B: Backend + 'static>(cx: T, width: usize) -> impl View<B> {
+        move || {
+            view! { cx,
+                <paragraph>{"#".repeat(width)}</paragraph>
+            }
+        }
+    }
+
+    let backend = TestBackend::new(6, 1);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut view = mount! {
+        <overlay>
+            <Bar width=3 place:anchor=right place:width=4/>
+        </overlay>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal
+        .backend()
+        .assert_buffer(&Buffer::with_lines(vec!["  ### "]));
+}
+
+#[test]
+fn scroll_view_clips_content() {
+    use std::cell::Cell;
+    use tui_rsx::event::{Event, EventResult, MouseButton, MouseEvent, MouseEventKind};
+
+    let backend = TestBackend::new(6, 4);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let state = Rc::new(RefCell::new(ScrollViewState::default()));
+    let offset = state.clone();
+    let clicked = Rc::new(Cell::new(false));
+    let on_click = clicked.clone();
+    let mut view = mount! {
+        <column>
+            <paragraph length=1>"header"</paragraph>
+            <scroll_view offset=offset>
+                <for each=0..5 let:i>
+                    <paragraph length=1>{format!("line {i}")}</paragraph>
+                </for>
+                <block length=1 title="end" on_click={
+                    let on_click = on_click.clone();
+                    move |_| on_click.set(true)
+                }/>
+            </scroll_view>
+        </column>
+    };
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal.backend().assert_buffer(&Buffer::with_lines(vec![
+        "header", "line 0", "line 1", "line 2",
+    ]));
+    assert_eq!(state.borrow().content_height(), 6);
+    assert_eq!(state.borrow().max_offset(), 3);
+
+    state.borrow_mut().scroll_down(10);
+    terminal
+        .draw(|f| {
+            view.view(f, f.size());
+        })
+        .unwrap();
+    terminal.backend().assert_buffer(&Buffer::with_lines(vec![
+        "header", "line 3", "line 4", "end   ",
+    ]));
+
+    let click = Event::Mouse(MouseEvent::new(
+        MouseEventKind::Down(MouseButton::Left),
+        1,
+        3,
+    ));
+    let rect = terminal.size().unwrap();
+    assert_eq!(view.handle_event(&click, rect), EventResult::Consumed);
+    assert!(clicked.get());
+}
+
+#[test]
+fn query_keyed_rects() {
+    use tui_rsx::event::{Event, MouseButton, MouseEvent, MouseEventKind};
+
+    let backend = TestBackend::new(10, 3);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let target = Rc::new(RefCell::new(None));
+    let mut view = mount! {
+        <column key="list" on_click={
+            let target = target.clone();
+            move |mouse: MouseEvent| {
+                let element = tui_rsx::element_at(mouse.column, mouse.row);
+                *target.borrow_mut() = element.map(|e| (e.is(&1), e.rect()));
+            }
+        }>
+            <block length=1 key=1 title="a"/>
+            <block length=1 key=2 title="b"/>
+        </column>
+    };
+    terminal.draw(|f| view.view(f, f.size())).unwrap();
+
+    assert_eq!(view.rect_of(&2), Some(Rect::new(0, 1, 10, 1)));
+    assert_eq!(tui_rsx::rect_of("list"), Some(Rect::new(0, 0, 10, 3)));
+    assert!(view.element_at(4, 2).unwrap().is("list"));
+    assert_eq!(view.element_at(10, 0), None);
+
+    let click = Event::Mouse(MouseEvent::new(
+        MouseEventKind::Down(MouseButton::Left),
+        3,
+        0,
+    ));
+    view.handle_event(&click, terminal.size().unwrap());
+    assert_eq!(*target.borrow(), Some((true, Rect::new(0, 0, 10, 1))));
+}
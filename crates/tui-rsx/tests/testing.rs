@@ -0,0 +1,80 @@
+use ratatui::style::{Color, Style};
+use std::{cell::RefCell, rc::Rc};
+use tui_rsx::{prelude::*, view};
+
+#[test]
+fn snapshot_helpers() {
+    use tui_rsx::{
+        assert_snapshot,
+        testing::{render_to_buffer, render_to_string},
+    };
+
+    let mut view = mount! {
+        <row>
+            <paragraph length=2 style=Style::default().fg(Color::Red)>"ab"</paragraph>
+            <paragraph>"cd"</paragraph>
+        </row>
+    };
+    assert_eq!(render_to_string(&mut view, 5, 1), "abcd ");
+    assert_snapshot!("styled_row", render_to_buffer(&mut view, 4, 1));
+}
+
+#[test]
+fn harness_flow() {
+    use tui_rsx::{
+        event::{KeyCode, KeyEvent},
+        testing::Harness,
+    };
+
+    let text = Rc::new(RefCell::new(String::new()));
+    let typed = text.clone();
+    let view = mount! {
+        <column on_key={
+            let text = text.clone();
+            move |key: KeyEvent| match key.code {
+                KeyCode::Char(c) => text.borrow_mut().push(c),
+                KeyCode::Backspace => {
+                    text.borrow_mut().pop();
+                }
+                _ => {}
+            }
+        }>
+            {view!(<paragraph>{typed.borrow().clone()}</paragraph>)}
+            <block length=1 key="clear" title="clear" on_click={
+                let text = text.clone();
+                move |_| text.borrow_mut().clear()
+            }/>
+        </column>
+    };
+    let mut harness = Harness::new(view, 6, 2);
+
+    harness.type_str("hello!");
+    harness.press(KeyCode::Backspace);
+    assert_eq!(harness.text(), "hello \nclear ");
+
+    assert_eq!(harness.rect_of("clear"), Some(Rect::new(0, 1, 6, 1)));
+    harness.click(2, 1);
+    assert_eq!(harness.text(), "      \nclear ");
+
+    harness.resize(8, 3);
+    assert_eq!(harness.text(), "        \n        \nclear   ");
+    assert_eq!(harness.rect_of("clear"), Some(Rect::new(0, 2, 8, 1)));
+}
+
+#[test]
+fn harness_tick() {
+    use std::time::Duration;
+    use tui_rsx::{clock, testing::Harness};
+
+    let start = clock::now();
+    let view = mount! {
+        <column>
+            {view!(<paragraph>{format!("{}s", (clock::now() - start).as_secs())}</paragraph>)}
+        </column>
+    };
+    let mut harness = Harness::new(view, 3, 1);
+    assert_eq!(harness.text(), "0s ");
+
+    harness.tick(Duration::from_secs(90));
+    assert_eq!(harness.text(), "90s");
+}
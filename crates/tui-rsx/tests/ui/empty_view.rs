@@ -0,0 +1,5 @@
+use tui_rsx::prelude::*;
+
+fn main() {
+    let _view = view!();
+}
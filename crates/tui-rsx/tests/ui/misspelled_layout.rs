@@ -0,0 +1,9 @@
+use tui_rsx::prelude::*;
+
+fn main() {
+    let _view = mount! {
+        <collumn>
+            <paragraph>"text"</paragraph>
+        </collumn>
+    };
+}
@@ -0,0 +1,7 @@
+use tui_rsx::prelude::*;
+
+fn main() {
+    let _view = mount! {
+        <block titel="x"/>
+    };
+}
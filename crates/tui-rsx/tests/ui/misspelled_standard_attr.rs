@@ -0,0 +1,22 @@
+use ratatui::backend::Backend;
+use tui_rsx::prelude::*;
+
+#[component]
+fn Viewer<T: Clone + 'static, B: Backend + 'static>(
+    cx: T,
+    #[prop(into)] text: String,
+) -> impl View<B> {
+    move || {
+        view! { cx,
+            <paragraph>{text.clone()}</paragraph>
+        }
+    }
+}
+
+fn main() {
+    let _view = mount! {
+        <column>
+            <Viewer lenght=1 text="text"/>
+        </column>
+    };
+}
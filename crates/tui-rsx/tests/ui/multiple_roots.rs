@@ -0,0 +1,8 @@
+use tui_rsx::prelude::*;
+
+fn main() {
+    let _view = mount! {
+        <column/>
+        <row/>
+    };
+}
@@ -0,0 +1,11 @@
+use tui_rsx::prelude::*;
+
+fn main() {
+    let _view = mount! {
+        <column>
+            <block length border-type=BorderType::Plain/>
+            <paragraph focusable=true on_key>"a"</paragraph>
+            <paragraph>{1 +}</paragraph>
+        </column>
+    };
+}
@@ -0,0 +1,10 @@
+use tui_rsx::prelude::*;
+
+fn main() {
+    let _view = mount! {
+        <column>
+            <block titel="x"/>
+            <paragraph length=1>{1 +}</paragraph>
+        </column>
+    };
+}
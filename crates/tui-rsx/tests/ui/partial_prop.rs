@@ -0,0 +1,11 @@
+use tui_rsx::prelude::*;
+
+fn main() {
+    let _items = prop! {
+        <>
+            <listItem styl=Style::default()>"a"</listItem>
+            <listItem length>"b"</listItem>
+            <listItem>{1 +}</listItem>
+        </>
+    };
+}
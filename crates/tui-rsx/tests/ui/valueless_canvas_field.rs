@@ -0,0 +1,9 @@
+use tui_rsx::prelude::*;
+
+fn main() {
+    let _view = mount! {
+        <canvas x_bounds=[0.0, 3.0] y_bounds=[0.0, 2.0]>
+            <line x1 y1=2.0 x2=3.0 y2=2.0/>
+        </canvas>
+    };
+}
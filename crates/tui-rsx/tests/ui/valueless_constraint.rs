@@ -0,0 +1,9 @@
+use tui_rsx::prelude::*;
+
+fn main() {
+    let _view = mount! {
+        <column>
+            <paragraph length>"text"</paragraph>
+        </column>
+    };
+}